@@ -1,9 +1,13 @@
-use super::compare::{permissive_parser_config, ParserConfig};
+use cvers::{permissive_parser_config, ParserConfig, Scheme};
 
-pub fn parse_arguments(args: Vec<String>) -> (ParserConfig, Vec::<String>) {
+use super::exit_on_error;
+
+pub fn parse_arguments(args: Vec<String>) -> (ParserConfig, Scheme, Vec::<String>) {
     let mut parser_config = permissive_parser_config();
+    let mut scheme = Scheme::Permissive;
     let mut mandatories_args: Vec<String> = Vec::new();
     let mut next_args_is_epoch_delimiter: bool = false;
+    let mut next_args_is_scheme: bool = false;
     for arg in args {
         let arg_str = arg.as_str();
         match arg_str {
@@ -13,17 +17,26 @@ pub fn parse_arguments(args: Vec<String>) -> (ParserConfig, Vec::<String>) {
             "--epoch" => {
                 next_args_is_epoch_delimiter = true;
             },
+            "--scheme" => {
+                next_args_is_scheme = true;
+            },
             _ => {
                 if next_args_is_epoch_delimiter {
                     parser_config.epoch_delimiter = arg.chars().next();
                     next_args_is_epoch_delimiter = false;
+                } else if next_args_is_scheme {
+                    scheme = match Scheme::from_name(arg_str) {
+                        Some(scheme) => scheme,
+                        None => exit_on_error(&format!("Invalid scheme '{scheme}'.", scheme=arg_str)),
+                    };
+                    next_args_is_scheme = false;
                 } else {
                     mandatories_args.push(arg)
                 }
             },
         }
     }
-    (parser_config, mandatories_args)
+    (parser_config, scheme, mandatories_args)
 }
 
 #[cfg(test)]
@@ -38,18 +51,18 @@ mod tests {
             String::from("second value")
         ];
 
-        let parsed_args: (ParserConfig, Vec::<String>) = parse_arguments(args.clone());
+        let parsed_args: (ParserConfig, Scheme, Vec::<String>) = parse_arguments(args.clone());
 
-        assert_eq!(parsed_args.1, args);
+        assert_eq!(parsed_args.2, args);
     }
 
     #[test]
     fn test_default_config() {
         let args: Vec<String> = Vec::new();
 
-        let parsed_args: (ParserConfig, Vec::<String>) = parse_arguments(args);
+        let parsed_args: (ParserConfig, Scheme, Vec::<String>) = parse_arguments(args);
 
-        assert_eq!(parsed_args.0, super::super::compare::permissive_parser_config());
+        assert_eq!(parsed_args.0, permissive_parser_config());
     }
 
     #[test]
@@ -66,13 +79,13 @@ mod tests {
             args[3].clone(),
         ];
 
-        let mut expected: ParserConfig = super::super::compare::permissive_parser_config();
+        let mut expected: ParserConfig = permissive_parser_config();
         expected.pre_release_touchs_digit = Some(true);
 
-        let parsed_args: (ParserConfig, Vec::<String>) = parse_arguments(args);
+        let parsed_args: (ParserConfig, Scheme, Vec::<String>) = parse_arguments(args);
 
         assert_eq!(parsed_args.0, expected);
-        assert_eq!(parsed_args.1, mandatory_args);
+        assert_eq!(parsed_args.2, mandatory_args);
     }
 
     #[test]
@@ -90,13 +103,41 @@ mod tests {
             args[4].clone(),
         ];
 
-        let mut expected: ParserConfig = super::super::compare::permissive_parser_config();
+        let mut expected: ParserConfig = permissive_parser_config();
         expected.epoch_delimiter = Some('|');
 
-        let parsed_args: (ParserConfig, Vec::<String>) = parse_arguments(args);
+        let parsed_args: (ParserConfig, Scheme, Vec::<String>) = parse_arguments(args);
 
         assert_eq!(parsed_args.0, expected);
-        assert_eq!(parsed_args.1, mandatory_args);
+        assert_eq!(parsed_args.2, mandatory_args);
     }
 
+    #[test]
+    fn test_set_scheme() {
+        let args: Vec<String> = vec![
+            String::from("verb"),
+            String::from("--scheme"),
+            String::from("semver"),
+            String::from("first value"),
+            String::from("second value")
+        ];
+        let mandatory_args: Vec<String> = vec![
+            args[0].clone(),
+            args[3].clone(),
+            args[4].clone(),
+        ];
+
+        let parsed_args: (ParserConfig, Scheme, Vec::<String>) = parse_arguments(args);
+
+        assert_eq!(parsed_args.0, permissive_parser_config());
+        assert_eq!(parsed_args.1, Scheme::Semver);
+        assert_eq!(parsed_args.2, mandatory_args);
+    }
+
+    #[test]
+    fn test_default_scheme() {
+        let parsed_args: (ParserConfig, Scheme, Vec::<String>) = parse_arguments(Vec::new());
+
+        assert_eq!(parsed_args.1, Scheme::Permissive);
+    }
 }
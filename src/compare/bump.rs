@@ -0,0 +1,171 @@
+use super::structs::{BuildBlock, Number, Version};
+
+/// A component of a [`Version`] that [`Version::bump`] can increment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    /// The number before the epoch delimiter.
+    Epoch,
+    /// The first number of the main chunk.
+    Major,
+    /// The second number of the main chunk.
+    Minor,
+    /// The third number of the main chunk.
+    Patch,
+    /// The number of the pre-release, like `3` in `rc3`.
+    Pre,
+    /// The build number.
+    Build,
+}
+
+impl Part {
+    /// Finds a part from its command line name, like `minor`.
+    pub fn from_name(name: &str) -> Option<Part> {
+        match name {
+            "epoch" => Some(Part::Epoch),
+            "major" => Some(Part::Major),
+            "minor" => Some(Part::Minor),
+            "patch" => Some(Part::Patch),
+            "pre" => Some(Part::Pre),
+            "build" => Some(Part::Build),
+            _ => None,
+        }
+    }
+}
+
+impl Version {
+    /// The version with `part` incremented and the components after it reset.
+    ///
+    /// A missing number counts as zero, so bumping the build of `1.0` gives
+    /// `1.0+1`. Bumping `major`, `minor` or `patch` of a pre-release whose
+    /// following numbers are zeros promotes it to its release instead, like
+    /// `1.0-rc3` to `1.0`. The epoch is bumped alone: the rest is kept.
+    ///
+    /// The spelling is kept: numbers keep their width (`2023.01.05` gives
+    /// `2023.01.06`), and the pre-release its case and `.` (`1.0-RC.3` gives
+    /// `1.0-RC.4`).
+    ///
+    /// `None` when bumping `pre` of a version without a pre-release number to
+    /// count from, like `1.0` or `1.0-alpha.beta`.
+    pub fn bump(&self, part: Part) -> Option<Version> {
+        let mut version: Version = self.clone();
+        match part {
+            Part::Epoch => {
+                version.epoch = Some(self.epoch.as_ref().map_or(Number::from(1), Number::incremented));
+            },
+            Part::Major | Part::Minor | Part::Patch => {
+                let index: usize = match part {
+                    Part::Major => 0,
+                    Part::Minor => 1,
+                    _ => 2,
+                };
+                let is_prerelease: bool = self.pre_release.is_some() || self.main.pre_letter.is_some();
+                let following_are_zeros: bool = self.main.numbers.iter().skip(index + 1).all(Number::is_zero);
+                if !(is_prerelease && following_are_zeros) {
+                    while version.main.numbers.len() <= index {
+                        version.main.numbers.push(Number::from(0));
+                    }
+                    version.main.numbers[index] = version.main.numbers[index].incremented();
+                    for number in version.main.numbers.iter_mut().skip(index + 1) {
+                        *number = number.zeroed();
+                    }
+                }
+                version.main.pre_letter = None;
+                version.main.post_letter = None;
+                version.main.dotted_letter = false;
+                version.pre_release = None;
+                version.build = None;
+            },
+            Part::Pre => {
+                let pre_release = version.pre_release.as_mut()?;
+                if pre_release.post_step.is_some() {
+                    return None
+                }
+                if pre_release.post_number.is_none() {
+                    pre_release.dotted = true;
+                }
+                pre_release.post_number = Some(pre_release.post_number.as_ref().map_or(Number::from(1), Number::incremented));
+                version.build = None;
+            },
+            Part::Build => {
+                let number: Number = self.build.as_ref().map_or(Number::from(1), |build| build.number.incremented());
+                version.build = Some(BuildBlock { number });
+            },
+        }
+        Some(version)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::config::permissive_parser_config;
+    use super::super::structs::ParserConfig;
+
+    fn bump(raw_version: &str, part: Part) -> Option<String> {
+        let parser_config: ParserConfig = ParserConfig { epoch_delimiter: Some('!'), pre_release_touchs_digit: Some(true) };
+        let version: Version = Version::parse_with(raw_version, &parser_config).unwrap();
+        version.bump(part).map(|bumped| bumped.to_string_with(&parser_config))
+    }
+
+    #[test]
+    fn test_bump_main_numbers() {
+        assert_eq!(bump("1.2.3", Part::Major), Some("2.0.0".to_string()));
+        assert_eq!(bump("1.2.3", Part::Minor), Some("1.3.0".to_string()));
+        assert_eq!(bump("1.2.3", Part::Patch), Some("1.2.4".to_string()));
+        assert_eq!(bump("1.2.3.4", Part::Minor), Some("1.3.0.0".to_string()));
+        assert_eq!(bump("1", Part::Patch), Some("1.0.1".to_string()));
+        assert_eq!(bump("1.9+7", Part::Minor), Some("1.10".to_string()));
+        assert_eq!(bump("2!1.0", Part::Major), Some("2!2.0".to_string()));
+    }
+
+    #[test]
+    fn test_bump_promotes_prerelease() {
+        assert_eq!(bump("1.0-rc3", Part::Minor), Some("1.0".to_string()));
+        assert_eq!(bump("1.0-rc3", Part::Major), Some("1.0".to_string()));
+        assert_eq!(bump("1.2-beta", Part::Major), Some("2.0".to_string()));
+        assert_eq!(bump("1.0a", Part::Patch), Some("1.0".to_string()));
+    }
+
+    #[test]
+    fn test_bump_pre_release() {
+        assert_eq!(bump("1.0-rc3", Part::Pre), Some("1.0-rc4".to_string()));
+        assert_eq!(bump("1.0-alpha+2", Part::Pre), Some("1.0-alpha.1".to_string()));
+        assert_eq!(bump("1.0-alpha.9", Part::Pre), Some("1.0-alpha.10".to_string()));
+        assert_eq!(bump("1.0-alpha.beta", Part::Pre), None);
+        assert_eq!(bump("1.0", Part::Pre), None);
+    }
+
+    #[test]
+    fn test_bump_keeps_spelling() {
+        assert_eq!(bump("2023.01.05", Part::Patch), Some("2023.01.06".to_string()));
+        assert_eq!(bump("2023.01.05", Part::Minor), Some("2023.02.00".to_string()));
+        assert_eq!(bump("2023.09", Part::Minor), Some("2023.10".to_string()));
+        assert_eq!(bump("1.0-RC3", Part::Pre), Some("1.0-RC4".to_string()));
+        assert_eq!(bump("1.0-rc.3", Part::Pre), Some("1.0-rc.4".to_string()));
+        assert_eq!(bump("1.0-Beta.09", Part::Pre), Some("1.0-Beta.10".to_string()));
+        assert_eq!(bump("1.0-RC", Part::Pre), Some("1.0-RC.1".to_string()));
+        assert_eq!(bump("01!1.0+007", Part::Build), Some("01!1.0+008".to_string()));
+        assert_eq!(bump("01!1.0", Part::Epoch), Some("02!1.0".to_string()));
+    }
+
+    #[test]
+    fn test_bump_build_and_epoch() {
+        assert_eq!(bump("1.0", Part::Build), Some("1.0+1".to_string()));
+        assert_eq!(bump("1.0-rc1+9", Part::Build), Some("1.0-rc1+10".to_string()));
+        assert_eq!(bump("1.0-rc1", Part::Epoch), Some("1!1.0-rc1".to_string()));
+        assert_eq!(bump("1!1.0", Part::Epoch), Some("2!1.0".to_string()));
+    }
+
+    #[test]
+    fn test_bumped_version_is_greater() {
+        for raw_version in ["1", "1.2.3", "1.0-rc3", "1:1.0-alpha.2+4"].iter() {
+            let version: Version = Version::parse_with(raw_version, &permissive_parser_config()).unwrap();
+            for part in [Part::Epoch, Part::Major, Part::Minor, Part::Patch, Part::Pre, Part::Build].iter() {
+                if let Some(bumped) = version.bump(*part) {
+                    assert!(bumped > version, "{:?} of {}", part, raw_version);
+                }
+            }
+        }
+    }
+}
@@ -1,19 +1,42 @@
 use std::cmp::Ordering;
 
-pub fn compare_with_operator(raw_version_a: &str, raw_version_b: &str, raw_operator: &str, parser_config: &super::structs::ParserConfig)-> bool{
-    let order = compare(raw_version_a, raw_version_b, &parser_config);
+use super::super::errors::ParseError;
+use super::super::schemes::{PermissiveScheme, VersionScheme};
+use super::comparison::Comparison;
+use super::operator::Operator;
 
-    (order == Ordering::Less && (raw_operator == "<<" || raw_operator == "<=")) ||
-        (order == Ordering::Greater && (raw_operator == ">>" || raw_operator == "=>")) ||
-        (order == Ordering::Equal && (raw_operator == "<=" || raw_operator == "==" || raw_operator == "=>")) ||
-        ((order == Ordering::Less || order == Ordering::Greater) && raw_operator == "!=")
+/// Checks `raw_version_a raw_operator raw_version_b`.
+///
+/// `raw_operator` is one of `<<`, `<=`, `==`, `=>`, `>>` or `!=`.
+pub fn compare_with_operator(raw_version_a: &str, raw_version_b: &str, raw_operator: &str, parser_config: &super::structs::ParserConfig)-> Result<bool, ParseError>{
+    let operator: Operator = raw_operator.parse()?;
+    let order = compare(raw_version_a, raw_version_b, parser_config)?;
+
+    Ok(operator.matches(order))
+}
+
+/// Orders `raw_version_a` relative to `raw_version_b`.
+pub fn compare(raw_version_a: &str, raw_version_b: &str, parser_config: &super::structs::ParserConfig)-> Result<Ordering, ParseError>{
+    let version_a: super::structs::Version = super::parse::parse_raw_version(raw_version_a, parser_config)?;
+    let version_b: super::structs::Version = super::parse::parse_raw_version(raw_version_b, parser_config)?;
+
+    Ok(version_a.cmp(&version_b))
+}
+
+/// Like [`compare`], telling which component decided the order.
+pub fn compare_detailed(raw_version_a: &str, raw_version_b: &str, parser_config: &super::structs::ParserConfig)-> Result<Comparison, ParseError>{
+    let version_a: super::structs::Version = super::parse::parse_raw_version(raw_version_a, parser_config)?;
+    let version_b: super::structs::Version = super::parse::parse_raw_version(raw_version_b, parser_config)?;
+
+    Ok(version_a.compare_detailed(&version_b))
 }
 
-pub fn compare(raw_version_a: &str, raw_version_b: &str, parser_config: &super::structs::ParserConfig)-> Ordering{
-    let version_a: super::structs::Version = super::parse::parse_raw_version(raw_version_a, parser_config);
-    let version_b: super::structs::Version = super::parse::parse_raw_version(raw_version_b, parser_config);
+/// Like [`compare`], but `None` when either string is not a version at all.
+/// See [`VersionScheme::try_compare`](super::super::schemes::VersionScheme::try_compare).
+pub fn try_compare(raw_version_a: &str, raw_version_b: &str, parser_config: &super::structs::ParserConfig)-> Result<Option<Ordering>, ParseError>{
+    let scheme: PermissiveScheme = PermissiveScheme::new(parser_config.clone());
 
-    version_a.cmp(&version_b)
+    scheme.try_compare(raw_version_a, raw_version_b)
 }
 
 
@@ -36,22 +59,22 @@ mod tests {
     }
 
     fn assert_equal(first: &str, second: &str, parser_config: &structs::ParserConfig){
-        assert_eq!(compare(first, second, &parser_config), Ordering::Equal);
-        assert_eq!(compare(second, first, &parser_config), Ordering::Equal);
+        assert_eq!(compare(first, second, parser_config).unwrap(), Ordering::Equal);
+        assert_eq!(compare(second, first, parser_config).unwrap(), Ordering::Equal);
     }
 
     fn python_parser_config() -> structs::ParserConfig {
-        return structs::ParserConfig {
+        structs::ParserConfig {
             epoch_delimiter: Some('!'),
             pre_release_touchs_digit: Some(true),
-        };
+        }
     }
 
     fn openssl_parser_config() -> structs::ParserConfig {
-        return structs::ParserConfig {
+        structs::ParserConfig {
             epoch_delimiter: None,
             pre_release_touchs_digit: Some(false)
-        };
+        }
     }
 
     #[test]
@@ -130,8 +153,8 @@ mod tests {
     }
     fn assert_not_equal(max: &str, min: &str, parser_config: &structs::ParserConfig){
 
-        assert_eq!(compare(max, min, parser_config), Ordering::Greater);
-        assert_eq!(compare(min, max, parser_config), Ordering::Less);
+        assert_eq!(compare(max, min, parser_config).unwrap(), Ordering::Greater);
+        assert_eq!(compare(min, max, parser_config).unwrap(), Ordering::Less);
     }
     #[test]
     fn test_not_equal_between_rc_version_and_release_version() {
@@ -280,29 +303,35 @@ mod tests {
         const MAX: &str = "2";
         const MIN: &str = "1";
 
-        assert!(compare_with_operator(MIN, MAX, "<<", &parser_config));
-        assert!(compare_with_operator(MIN, MAX, "<=", &parser_config));
-        assert_eq!(compare_with_operator(MIN, MAX, "==", &parser_config), false);
-        assert!(compare_with_operator(MAX, MIN, "=>", &parser_config));
-        assert!(compare_with_operator(MAX, MIN, ">>", &parser_config));
-        assert_eq!(compare_with_operator(MAX, MIN, "<<", &parser_config), false);
-        assert_eq!(compare_with_operator(MAX, MIN, "<=", &parser_config), false);
-        assert_eq!(compare_with_operator(MIN, MAX, "=>", &parser_config), false);
-        assert_eq!(compare_with_operator(MIN, MAX, ">>", &parser_config), false);
-        assert!(compare_with_operator(MIN, MAX, "!=", &parser_config));
-        assert!(compare_with_operator(MIN, MAX, "!=", &parser_config));
+        assert!(compare_with_operator(MIN, MAX, "<<", &parser_config).unwrap());
+        assert!(compare_with_operator(MIN, MAX, "<=", &parser_config).unwrap());
+        assert_eq!(compare_with_operator(MIN, MAX, "==", &parser_config), Ok(false));
+        assert!(compare_with_operator(MAX, MIN, "=>", &parser_config).unwrap());
+        assert!(compare_with_operator(MAX, MIN, ">>", &parser_config).unwrap());
+        assert_eq!(compare_with_operator(MAX, MIN, "<<", &parser_config), Ok(false));
+        assert_eq!(compare_with_operator(MAX, MIN, "<=", &parser_config), Ok(false));
+        assert_eq!(compare_with_operator(MIN, MAX, "=>", &parser_config), Ok(false));
+        assert_eq!(compare_with_operator(MIN, MAX, ">>", &parser_config), Ok(false));
+        assert!(compare_with_operator(MIN, MAX, "!=", &parser_config).unwrap());
+        assert!(compare_with_operator(MIN, MAX, "!=", &parser_config).unwrap());
     }
     #[test]
     fn test_match_operator_for_same_version() {
         let parser_config: structs::ParserConfig = permissive_parser_config();
         const VERSION: &str = "2";
 
-        assert_eq!(compare_with_operator(VERSION, VERSION, "<<", &parser_config), false);
-        assert!(compare_with_operator(VERSION, VERSION, "<=", &parser_config));
-        assert!(compare_with_operator(VERSION, VERSION, "==", &parser_config));
-        assert!(compare_with_operator(VERSION, VERSION, "=>", &parser_config));
-        assert_eq!(compare_with_operator(VERSION, VERSION, ">>", &parser_config), false);
-        assert_eq!(compare_with_operator(VERSION, VERSION, "!=", &parser_config), false);
+        assert_eq!(compare_with_operator(VERSION, VERSION, "<<", &parser_config), Ok(false));
+        assert!(compare_with_operator(VERSION, VERSION, "<=", &parser_config).unwrap());
+        assert!(compare_with_operator(VERSION, VERSION, "==", &parser_config).unwrap());
+        assert!(compare_with_operator(VERSION, VERSION, "=>", &parser_config).unwrap());
+        assert_eq!(compare_with_operator(VERSION, VERSION, ">>", &parser_config), Ok(false));
+        assert_eq!(compare_with_operator(VERSION, VERSION, "!=", &parser_config), Ok(false));
+    }
+    #[test]
+    fn test_invalid_operator() {
+        let parser_config: structs::ParserConfig = permissive_parser_config();
+
+        assert!(compare_with_operator("1", "2", "<>", &parser_config).is_err());
     }
 
 }
@@ -0,0 +1,163 @@
+use std::cmp::{max, Ordering};
+use std::fmt;
+
+use super::structs::{Number, Version};
+
+/// A part of a [`Version`] that can decide an order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component {
+    Epoch,
+    /// The number at this position of the main chunk, counted from 1.
+    MainNumber(usize),
+    PreLetter,
+    PostLetter,
+    /// Whether there is a pre-release at all.
+    PreRelease,
+    Step,
+    PostStep,
+    PostNumber,
+    Build,
+}
+
+/// The order of two versions and the first component that differs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    pub ordering: Ordering,
+    /// The deciding component, with the values compared, `none` for a missing one.
+    /// `None` when the versions are equal.
+    pub decided_by: Option<(Component, String, String)>,
+}
+
+impl Version {
+    /// Like `cmp`, telling which component decided the order.
+    pub fn compare_detailed(&self, other: &Version) -> Comparison {
+        let decide = |ordering: Ordering, component: Component, left: String, right: String| -> Option<Comparison> {
+            match ordering {
+                Ordering::Equal => None,
+                ordering => Some(Comparison { ordering, decided_by: Some((component, left, right)) }),
+            }
+        };
+        let text = |value: Option<String>| value.unwrap_or_else(|| "none".to_string());
+
+        let epoch = |version: &Version| text(version.epoch.as_ref().map(Number::to_string));
+        if let Some(comparison) = decide(self.cmp_epoch(other), Component::Epoch, epoch(self), epoch(other)) {
+            return comparison
+        }
+
+        let zero: Number = Number::from(0);
+        for index in 0..max(self.main.numbers.len(), other.main.numbers.len()) {
+            let left: &Number = self.main.numbers.get(index).unwrap_or(&zero);
+            let right: &Number = other.main.numbers.get(index).unwrap_or(&zero);
+            if let Some(comparison) = decide(left.cmp(right), Component::MainNumber(index + 1), left.to_string(), right.to_string()) {
+                return comparison
+            }
+        }
+        let pre_letter = |version: &Version| text(version.main.pre_letter.map(String::from));
+        if let Some(comparison) = decide(self.main.cmp_pre_letter(&other.main), Component::PreLetter, pre_letter(self), pre_letter(other)) {
+            return comparison
+        }
+        let post_letter = |version: &Version| text(version.main.post_letter.map(String::from));
+        if let Some(comparison) = decide(self.main.cmp_post_letter(&other.main), Component::PostLetter, post_letter(self), post_letter(other)) {
+            return comparison
+        }
+
+        match (&self.pre_release, &other.pre_release) {
+            (Some(x), Some(y)) => {
+                if let Some(comparison) = decide(x.cmp_step(y), Component::Step, x.step.clone(), y.step.clone()) {
+                    return comparison
+                }
+                if let Some(comparison) = decide(x.cmp_post_step(y), Component::PostStep, text(x.post_step.clone()), text(y.post_step.clone())) {
+                    return comparison
+                }
+                let post_number = |number: &Option<Number>| text(number.as_ref().map(Number::to_string));
+                if let Some(comparison) = decide(x.cmp_post_number(y), Component::PostNumber, post_number(&x.post_number), post_number(&y.post_number)) {
+                    return comparison
+                }
+            },
+            (x, y) => {
+                let pre_release = |block: &Option<_>| text(block.as_ref().map(ToString::to_string));
+                if let Some(comparison) = decide(self.cmp_prerelease(other), Component::PreRelease, pre_release(x), pre_release(y)) {
+                    return comparison
+                }
+            },
+        }
+
+        let build = |version: &Version| text(version.build.as_ref().map(ToString::to_string));
+        if let Some(comparison) = decide(self.cmp_build(other), Component::Build, build(self), build(other)) {
+            return comparison
+        }
+        Comparison { ordering: Ordering::Equal, decided_by: None }
+    }
+}
+
+impl fmt::Display for Component {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Component::Epoch => write!(f, "epoch"),
+            Component::MainNumber(position) => write!(f, "main number #{}", position),
+            Component::PreLetter => write!(f, "pre-release letter"),
+            Component::PostLetter => write!(f, "post-release letter"),
+            Component::PreRelease => write!(f, "pre-release"),
+            Component::Step => write!(f, "pre-release step"),
+            Component::PostStep => write!(f, "pre-release post step"),
+            Component::PostNumber => write!(f, "pre-release number"),
+            Component::Build => write!(f, "build"),
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol: &str = match self.ordering {
+            Ordering::Less => "<",
+            Ordering::Equal => "=",
+            Ordering::Greater => ">",
+        };
+        match &self.decided_by {
+            Some((component, left, right)) => write!(f, "{}: {} {} {}", component, left, symbol, right),
+            None => write!(f, "every component is equal"),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::structs::ParserConfig;
+
+    fn explain(a: &str, b: &str) -> String {
+        let parser_config: ParserConfig = ParserConfig { epoch_delimiter: Some(':'), pre_release_touchs_digit: Some(false) };
+        let version_a: Version = Version::parse_with(a, &parser_config).unwrap();
+        let version_b: Version = Version::parse_with(b, &parser_config).unwrap();
+        version_a.compare_detailed(&version_b).to_string()
+    }
+
+    #[test]
+    fn test_deciding_component() {
+        assert_eq!(explain("1.10", "1.9"), "main number #2: 10 > 9");
+        assert_eq!(explain("1.0", "1.0.1"), "main number #3: 0 < 1");
+        assert_eq!(explain("1:1.0", "2.0"), "epoch: 1 > none");
+        assert_eq!(explain("1.0.2e", "1.0.2f"), "post-release letter: e < f");
+        assert_eq!(explain("1.0-rc1", "1.0"), "pre-release: rc1 < none");
+        assert_eq!(explain("1.0-alpha", "1.0-beta"), "pre-release step: alpha < beta");
+        assert_eq!(explain("1.0-alpha.beta", "1.0-alpha"), "pre-release post step: beta > none");
+        assert_eq!(explain("1.0-rc10", "1.0-rc9"), "pre-release number: 10 > 9");
+        assert_eq!(explain("1.0+2", "1.0"), "build: 2 > none");
+        assert_eq!(explain("1.0", "1.0.0"), "every component is equal");
+    }
+
+    #[test]
+    fn test_agrees_with_cmp() {
+        let raw_versions = ["1", "1.0.1", "1:1", "1.0-rc1", "1.0-rc2", "1.0-alpha.beta", "1.0+1", "1.0a", "2.0-rc1+3"];
+        let parser_config: ParserConfig = ParserConfig { epoch_delimiter: Some(':'), pre_release_touchs_digit: Some(false) };
+        let versions: Vec<Version> = raw_versions.iter().map(|v| Version::parse_with(v, &parser_config).unwrap()).collect();
+        for a in versions.iter() {
+            for b in versions.iter() {
+                let comparison: Comparison = a.compare_detailed(b);
+                assert_eq!(comparison.ordering, a.cmp(b), "{} {}", a, b);
+                assert_eq!(comparison.decided_by.is_none(), a == b, "{} {}", a, b);
+            }
+        }
+    }
+}
@@ -1,10 +1,11 @@
 use super::structs::ParserConfig;
 
+/// Default configuration: `:` as epoch delimiter, touching letters rejected.
 pub fn permissive_parser_config() -> ParserConfig {
-    return super::structs::ParserConfig {
+    super::structs::ParserConfig {
         epoch_delimiter: Some(':'),
         pre_release_touchs_digit: None
-    };
+    }
 }
 
 
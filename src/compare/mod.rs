@@ -1,9 +1,21 @@
+mod bump;
+#[allow(clippy::module_inception)]
 mod compare;
+mod comparison;
 mod config;
+mod operator;
 mod parse;
+mod requirement;
 mod structs;
 
+pub use bump::Part;
 pub use compare::compare;
 pub use compare::compare_with_operator;
+pub use compare::compare_detailed;
+pub use comparison::{Comparison, Component};
+pub use compare::try_compare;
 pub use config::permissive_parser_config;
-pub use structs::ParserConfig;
\ No newline at end of file
+pub use operator::Operator;
+pub use parse::parse_raw_version;
+pub use requirement::{Comparator, VersionReq};
+pub use structs::{BuildBlock, MainBlock, Number, ParserConfig, PrereleaseBlock, Version};
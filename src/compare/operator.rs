@@ -0,0 +1,104 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use super::super::errors::ParseError;
+
+/// Relation checked between two versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    /// `<<`
+    StrictlyLess,
+    /// `<=`
+    LessOrEqual,
+    /// `==`
+    Equal,
+    /// `=>`
+    GreaterOrEqual,
+    /// `>>`
+    StrictlyGreater,
+    /// `!=`
+    NotEqual,
+}
+
+impl Operator {
+    /// Tells if `order`, the result of comparing `a` to `b`, satisfies `a operator b`.
+    pub fn matches(self, order: Ordering) -> bool {
+        match self {
+            Operator::StrictlyLess => order == Ordering::Less,
+            Operator::LessOrEqual => order != Ordering::Greater,
+            Operator::Equal => order == Ordering::Equal,
+            Operator::GreaterOrEqual => order != Ordering::Less,
+            Operator::StrictlyGreater => order == Ordering::Greater,
+            Operator::NotEqual => order != Ordering::Equal,
+        }
+    }
+}
+
+impl FromStr for Operator {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Operator, ParseError> {
+        match s {
+            "<<" => Ok(Operator::StrictlyLess),
+            "<=" => Ok(Operator::LessOrEqual),
+            "==" => Ok(Operator::Equal),
+            "=>" => Ok(Operator::GreaterOrEqual),
+            ">>" => Ok(Operator::StrictlyGreater),
+            "!=" => Ok(Operator::NotEqual),
+            _ => Err(ParseError::InvalidOperator { operator: s.to_string(), offset: 0 }),
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Operator::StrictlyLess => "<<",
+            Operator::LessOrEqual => "<=",
+            Operator::Equal => "==",
+            Operator::GreaterOrEqual => "=>",
+            Operator::StrictlyGreater => ">>",
+            Operator::NotEqual => "!=",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_assert_operators() {
+        for operator in ["<<", "<=", "==", "=>", ">>", "!="].iter() {
+            assert_eq!(operator.parse::<Operator>().unwrap().to_string(), *operator);
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_short_operators() {
+        for operator in ["<", ">", "=", ">="].iter() {
+            assert!(operator.parse::<Operator>().is_err(), "{}", operator);
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_operator() {
+        assert_eq!(
+            "<>".parse::<Operator>(),
+            Err(ParseError::InvalidOperator { operator: "<>".to_string(), offset: 0 })
+        );
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(Operator::StrictlyLess.matches(Ordering::Less));
+        assert!(!Operator::StrictlyLess.matches(Ordering::Equal));
+        assert!(Operator::LessOrEqual.matches(Ordering::Equal));
+        assert!(Operator::GreaterOrEqual.matches(Ordering::Greater));
+        assert!(!Operator::GreaterOrEqual.matches(Ordering::Less));
+        assert!(Operator::NotEqual.matches(Ordering::Greater));
+    }
+}
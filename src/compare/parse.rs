@@ -1,149 +1,250 @@
-use super::super::errors;
-use super::structs::{Version, MainBlock, PrereleaseBlock, BuildBlock, ParserConfig};
-
-pub fn parse_raw_version(raw_version: &str, parser_config: &ParserConfig) -> Version{
-    let (raw_epoch, raw_tail): (&str, &str) = split_epoch_tail(raw_version, parser_config);
-    let epoch: Option<u8> = parse_epoch(raw_epoch);
-
-    let (raw_main, raw_prerelease, raw_build): (&str, &str, &str) = split_version_prerelease_build(&raw_tail);
-    let main_block: MainBlock = parse_main(raw_main, parser_config);
-    let prerelease_block: Option<PrereleaseBlock> = parse_prerelease(&raw_prerelease);
-    let build_block: Option<BuildBlock> = parse_build(&raw_build);
-    Version {
-        epoch: epoch,
+use super::super::errors::ParseError;
+use super::structs::{Version, MainBlock, Number, PrereleaseBlock, BuildBlock, ParserConfig};
+
+/// Parses `raw_version` according to `parser_config`.
+pub fn parse_raw_version(raw_version: &str, parser_config: &ParserConfig) -> Result<Version, ParseError> {
+    let (raw_epoch, raw_tail, tail_offset): (&str, &str, usize) = split_epoch_tail(raw_version, parser_config)?;
+    let epoch: Option<Number> = parse_epoch(raw_epoch)?;
+
+    let ((raw_main, main_offset), raw_prerelease, raw_build) = split_version_prerelease_build(raw_tail, tail_offset)?;
+    let main_block: MainBlock = parse_main(raw_main, main_offset, parser_config)?;
+    let prerelease_block: Option<PrereleaseBlock> = match raw_prerelease {
+        Some((s, offset)) => Some(parse_prerelease(s, offset)?),
+        None => None,
+    };
+    let build_block: Option<BuildBlock> = match raw_build {
+        Some((s, offset)) => Some(parse_build(s, offset)?),
+        None => None,
+    };
+    Ok(Version {
+        epoch,
         main: main_block,
         pre_release: prerelease_block,
         build: build_block,
-    }
+    })
 }
 
-fn parse_epoch(raw_epoch: &str) -> Option<u8> {
+fn parse_epoch(raw_epoch: &str) -> Result<Option<Number>, ParseError> {
     match raw_epoch {
-        "" => None,
-        s => Some(s.parse().unwrap()),
+        "" => Ok(None),
+        s => Ok(Some(parse_number(s, 0)?)),
     }
 }
 
-fn split_epoch_tail<'a>(s: &'a str, parser_config: &ParserConfig) -> (&'a str, &'a str) {
-    if parser_config.epoch_delimiter == None {
-        return ("", s)
-    }
-    let delimiter = parser_config.epoch_delimiter.unwrap();
-    let splitted: Vec<&str> = s.split(delimiter).collect();
-    match splitted.len() {
-        1 => ("", splitted[0]),
-        2 => (splitted[0], splitted[1]),
-        _ => {
-            let message = format!(
-                "Error: more than one '{delimiter}' character for epoch",
-                delimiter=delimiter,
-            ).as_str().to_owned();
-            errors::exit_on_error(&message);
-        },
+fn parse_number(s: &str, offset: usize) -> Result<Number, ParseError> {
+    s.parse().map_err(|e: ParseError| e.shifted(offset))
+}
+
+fn split_epoch_tail<'a>(s: &'a str, parser_config: &ParserConfig) -> Result<(&'a str, &'a str, usize), ParseError> {
+    let delimiter = match parser_config.epoch_delimiter {
+        None => return Ok(("", s, 0)),
+        Some(delimiter) => delimiter,
+    };
+    match split_str(s, delimiter, 0)? {
+        ((tail, offset), None) => Ok(("", tail, offset)),
+        ((epoch, _), Some((tail, offset))) => Ok((epoch, tail, offset)),
     }
 }
 
-fn split_str(s: &str, delimiter: char) -> (&str, &str) {
-    let splitted: Vec<&str> = s.split(delimiter).collect();
-    match splitted.len() {
-        1 => (splitted[0], ""),
-        2 => (splitted[0], splitted[1]),
-        _ => {
-            let message = format!(
-                "Error: more than one '{delimiter}' character",
-                delimiter=delimiter.to_string()
-            ).as_str().to_owned();
-            errors::exit_on_error(&message);
+type Located<'a> = (&'a str, usize);
+
+/// Splits `s` (found at `offset` in the raw version) on the single occurrence of `delimiter`.
+fn split_str(s: &str, delimiter: char, offset: usize) -> Result<(Located<'_>, Option<Located<'_>>), ParseError> {
+    let mut positions = s.match_indices(delimiter).map(|(index, _)| index);
+    match (positions.next(), positions.next()) {
+        (None, _) => Ok(((s, offset), None)),
+        (Some(index), None) => {
+            let second_start = index + delimiter.len_utf8();
+            Ok(((&s[..index], offset), Some((&s[second_start..], offset + second_start))))
         },
+        (Some(_), Some(index)) => Err(ParseError::DuplicateDelimiter { delimiter, offset: offset + index }),
     }
 }
 
-fn split_version_prerelease_build(s: &str) -> (&str, &str, &str) {
-    let (part_1, part_2): (&str, &str) = split_str(s, '-');
-    match part_2.len() {
-        0 => {
-            let (subpart_1, subpart_2): (&str, &str) = split_str(part_1, '+');
-            (subpart_1, "", subpart_2)
+fn split_version_prerelease_build(s: &str, offset: usize) -> Result<(Located<'_>, Option<Located<'_>>, Option<Located<'_>>), ParseError> {
+    let (part_1, part_2) = split_str(s, '-', offset)?;
+    match part_2 {
+        None => {
+            let (subpart_1, subpart_2) = split_str(part_1.0, '+', part_1.1)?;
+            Ok((subpart_1, None, subpart_2))
         },
-        _ => {
-            let (subpart_1, subpart_2): (&str, &str) = split_str(part_2, '+');
-            (part_1, subpart_1, subpart_2)
+        Some(part_2) => {
+            let (subpart_1, subpart_2) = split_str(part_2.0, '+', part_2.1)?;
+            Ok((part_1, Some(subpart_1), subpart_2))
         },
     }
 }
 
-fn parse_main(raw_main_block: &str, parser_config: &ParserConfig) -> MainBlock {
-    let mut main_version_numbers: Vec<u32> = Vec::new();
+fn parse_main(raw_main_block: &str, offset: usize, parser_config: &ParserConfig) -> Result<MainBlock, ParseError> {
+    let mut main_version_numbers: Vec<Number> = Vec::new();
     let mut pre_main_letter: Option<char> = None;
     let mut post_main_letter: Option<char> = None;
+    let mut dotted_letter: bool = false;
+    let mut subversion_offset: usize = offset;
     for subversion in raw_main_block.split('.'){
-        if last_char_is_letter(&subversion) {
-            if subversion.chars().count() > 1 {
-                let index_without_last_char: usize = subversion.chars().count() - 1;
-                main_version_numbers.push(subversion[0..index_without_last_char].parse().unwrap());
-            }
-            let last_char: Option<char> = subversion.chars().last();
-            match parser_config.pre_release_touchs_digit {
-                Some(true) => {pre_main_letter = last_char;},
-                Some(false) => {post_main_letter = last_char;},
-                None => {
-                    let message = format!(
-                        "Error: invalid letter ('{letter}') in '{block}'",
-                        letter=last_char.unwrap(),
-                        block=raw_main_block.to_string()
-                    ).as_str().to_owned();
-                    errors::exit_on_error(&message);
-                },
-            }
-        } else {
-            main_version_numbers.push(subversion.parse().unwrap());
+        match last_letter(subversion) {
+            Some((index, letter)) => {
+                if index > 0 {
+                    main_version_numbers.push(parse_number(&subversion[..index], subversion_offset)?);
+                } else {
+                    dotted_letter = subversion_offset > offset;
+                }
+                match parser_config.pre_release_touchs_digit {
+                    Some(true) => {pre_main_letter = Some(letter);},
+                    Some(false) => {post_main_letter = Some(letter);},
+                    None => {
+                        return Err(ParseError::InvalidLetter { letter, offset: subversion_offset + index })
+                    },
+                }
+            },
+            None => {
+                main_version_numbers.push(parse_number(subversion, subversion_offset)?);
+            },
         }
+        subversion_offset += subversion.len() + 1;
     }
-    MainBlock {
+    Ok(MainBlock {
         numbers: main_version_numbers,
         pre_letter: pre_main_letter,
         post_letter: post_main_letter,
-    }
+        dotted_letter,
+    })
 }
 
-fn last_char_is_letter(s: &str) -> bool {
-    ! s.chars().last().unwrap().is_digit(10)
+fn last_letter(s: &str) -> Option<(usize, char)> {
+    s.char_indices().last().filter(|(_, c)| c.is_alphabetic())
 }
 
-fn parse_prerelease(raw_prerelease: &str) -> Option<PrereleaseBlock> {
-    if raw_prerelease == "" {
-        return None
+fn parse_prerelease(raw_prerelease: &str, offset: usize) -> Result<PrereleaseBlock, ParseError> {
+    if raw_prerelease.is_empty() {
+        return Err(ParseError::EmptyComponent { offset })
     }
 
     let step: String;
-    let mut post_number: Option<u8> = None;
+    let mut written_step: Option<String> = None;
+    let mut dotted: bool = false;
+    let mut post_number: Option<Number> = None;
     let mut post_step: Option<String> = None;
-    let (raw_step, raw_second_elem): (&str, &str) = split_str(raw_prerelease, '.');
-    if raw_second_elem.len() > 0 {
-        step = raw_step.parse().unwrap();
-        if raw_second_elem.chars().nth(0).unwrap().is_digit(10) {
-            post_number = Some(raw_second_elem.parse().unwrap());
-        } else {
-            post_step = Some(raw_second_elem.parse().unwrap());
+    let ((raw_step, _), raw_second_elem) = split_str(raw_prerelease, '.', offset)?;
+    if let Some((raw_second_elem, second_offset)) = raw_second_elem {
+        if raw_step.is_empty() {
+            return Err(ParseError::EmptyComponent { offset })
         }
-    } else if raw_prerelease.len() > 2 && raw_prerelease[..2].to_lowercase() == "rc".to_string() {
+        step = raw_step.to_string();
+        match raw_second_elem.chars().next() {
+            None => return Err(ParseError::EmptyComponent { offset: second_offset }),
+            Some(c) if c.is_ascii_digit() => {
+                post_number = Some(parse_number(raw_second_elem, second_offset)?);
+                dotted = true;
+            },
+            Some(_) => {
+                post_step = Some(raw_second_elem.to_string());
+            },
+        }
+    } else if raw_prerelease.len() > 2 && raw_prerelease.get(..2).map(|s| s.to_lowercase()) == Some("rc".to_string()) {
         step = "rc".to_string();
-        post_number = Some(raw_prerelease[2..].parse().unwrap());
+        written_step = Some(raw_prerelease[..2].to_string());
+        post_number = Some(parse_number(&raw_prerelease[2..], offset + 2)?);
     } else {
-       step = raw_prerelease.parse().unwrap();
+       step = raw_prerelease.to_string();
     }
-    Some(PrereleaseBlock {
-        step: step,
-        post_number: post_number,
-        post_step: post_step,
+    Ok(PrereleaseBlock {
+        written_step: written_step.unwrap_or_else(|| step.clone()),
+        dotted,
+        step,
+        post_number,
+        post_step,
     })
 }
 
-fn parse_build(raw_build: &str) -> Option<BuildBlock> {
-    match raw_build {
-        "" => None,
-        s => Some(BuildBlock {
-            number: s.parse().unwrap(),
-        })
+fn parse_build(raw_build: &str, offset: usize) -> Result<BuildBlock, ParseError> {
+    Ok(BuildBlock {
+        number: parse_number(raw_build, offset)?,
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::config::permissive_parser_config;
+
+    fn parse_error(raw_version: &str) -> ParseError {
+        parse_raw_version(raw_version, &permissive_parser_config()).unwrap_err()
+    }
+
+    #[test]
+    fn test_parse_full_version() {
+        let version: Version = parse_raw_version("1:2.0-rc3+4", &permissive_parser_config()).unwrap();
+
+        assert_eq!(version.epoch, Some(Number::from(1)));
+        assert_eq!(version.main.numbers, vec![Number::from(2), Number::from(0)]);
+        assert_eq!(version.pre_release.as_ref().unwrap().step, "rc");
+        assert_eq!(version.pre_release.as_ref().unwrap().post_number, Some(Number::from(3)));
+        assert_eq!(version.build.as_ref().unwrap().number, Number::from(4));
+    }
+
+    #[test]
+    fn test_unexpected_character_in_main_block() {
+        assert_eq!(parse_error("1.x.3"), ParseError::InvalidLetter { letter: 'x', offset: 2 });
+        assert_eq!(parse_error("1.2%"), ParseError::UnexpectedCharacter { character: '%', offset: 3 });
+    }
+
+    #[test]
+    fn test_unexpected_character_in_epoch() {
+        assert_eq!(parse_error("a:1"), ParseError::UnexpectedCharacter { character: 'a', offset: 0 });
+    }
+
+    #[test]
+    fn test_unexpected_character_in_rc_number() {
+        assert_eq!(parse_error("1.0-rc1x"), ParseError::UnexpectedCharacter { character: 'x', offset: 7 });
+    }
+
+    #[test]
+    fn test_numbers_beyond_u64() {
+        let raw_version: &str = "300:20231015123045.184467440737095516160-rc300.1+99999999999999999999999";
+        let version: Version = parse_raw_version(raw_version, &permissive_parser_config()).unwrap();
+
+        assert_eq!(version.epoch, Some(Number::from(300)));
+        assert_eq!(version.main.numbers[1].digits(), "184467440737095516160");
+        assert_eq!(version.build.as_ref().unwrap().number.digits(), "99999999999999999999999");
+        assert_eq!(version.to_string(), raw_version);
+    }
+
+    #[test]
+    fn test_duplicate_epoch_delimiter() {
+        assert_eq!(parse_error("1:2:3"), ParseError::DuplicateDelimiter { delimiter: ':', offset: 3 });
+    }
+
+    #[test]
+    fn test_duplicate_prerelease_delimiter() {
+        assert_eq!(parse_error("1:1.0-a-b"), ParseError::DuplicateDelimiter { delimiter: '-', offset: 7 });
+    }
+
+    #[test]
+    fn test_empty_component() {
+        assert_eq!(parse_error(""), ParseError::EmptyComponent { offset: 0 });
+        assert_eq!(parse_error("1..3"), ParseError::EmptyComponent { offset: 2 });
+        assert_eq!(parse_error("1.0-"), ParseError::EmptyComponent { offset: 4 });
+        assert_eq!(parse_error("1.0+"), ParseError::EmptyComponent { offset: 4 });
+        assert_eq!(parse_error("1.0-alpha."), ParseError::EmptyComponent { offset: 10 });
+    }
+
+    #[test]
+    fn test_invalid_letter() {
+        assert_eq!(parse_error("1.0.2e"), ParseError::InvalidLetter { letter: 'e', offset: 5 });
+    }
+
+    #[test]
+    fn test_letter_accepted_by_config() {
+        let parser_config = ParserConfig {
+            epoch_delimiter: None,
+            pre_release_touchs_digit: Some(false),
+        };
+
+        let version: Version = parse_raw_version("1.0.2e", &parser_config).unwrap();
+
+        assert_eq!(version.main.numbers, vec![Number::from(1), Number::from(0), Number::from(2)]);
+        assert_eq!(version.main.post_letter, Some('e'));
     }
 }
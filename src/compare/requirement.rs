@@ -0,0 +1,131 @@
+use super::super::errors::ParseError;
+use super::operator::Operator;
+use super::parse::parse_raw_version;
+use super::structs::{ParserConfig, Version};
+
+/// One `operator version` constraint of a [`VersionReq`].
+#[derive(Debug)]
+pub struct Comparator {
+    pub operator: Operator,
+    pub version: Version,
+}
+
+/// Comma-separated list of constraints, like `>=1.2, <2.0`.
+///
+/// A version satisfies the requirement if it satisfies every constraint.
+/// A constraint without operator means `==`.
+#[derive(Debug)]
+pub struct VersionReq {
+    pub comparators: Vec<Comparator>,
+}
+
+impl VersionReq {
+    /// Parses `raw_requirement`, reading each version according to `parser_config`.
+    pub fn parse(raw_requirement: &str, parser_config: &ParserConfig) -> Result<VersionReq, ParseError> {
+        let mut comparators: Vec<Comparator> = Vec::new();
+        let mut offset: usize = 0;
+        for raw_comparator in raw_requirement.split(',') {
+            comparators.push(parse_comparator(raw_comparator, offset, parser_config)?);
+            offset += raw_comparator.len() + 1;
+        }
+        Ok(VersionReq { comparators })
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        self.comparators.iter().all(|comparator| comparator.matches(version))
+    }
+}
+
+impl Comparator {
+    pub fn matches(&self, version: &Version) -> bool {
+        self.operator.matches(version.cmp(&self.version))
+    }
+}
+
+fn parse_comparator(raw_comparator: &str, offset: usize, parser_config: &ParserConfig) -> Result<Comparator, ParseError> {
+    let operator_start: usize = raw_comparator.len() - raw_comparator.trim_start().len();
+    let trimmed: &str = raw_comparator.trim();
+    if trimmed.is_empty() {
+        return Err(ParseError::EmptyComponent { offset: offset + operator_start })
+    }
+    let operator_len: usize = trimmed.find(|c| !"<>=!".contains(c)).unwrap_or(trimmed.len());
+    let operator: Operator = match &trimmed[..operator_len] {
+        "" => Operator::Equal,
+        // Requirements also accept the usual `<`, `>`, `=` and `>=` spellings.
+        "<" => Operator::StrictlyLess,
+        ">" => Operator::StrictlyGreater,
+        "=" => Operator::Equal,
+        ">=" => Operator::GreaterOrEqual,
+        raw_operator => raw_operator.parse().map_err(|e: ParseError| e.shifted(offset + operator_start))?,
+    };
+    let raw_version: &str = trimmed[operator_len..].trim_start();
+    let version_start: usize = offset + operator_start + trimmed.len() - raw_version.len();
+    let version: Version = parse_raw_version(raw_version, parser_config).map_err(|e| e.shifted(version_start))?;
+    Ok(Comparator { operator, version })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::config::permissive_parser_config;
+
+    fn satisfies(raw_version: &str, raw_requirement: &str) -> bool {
+        let parser_config: ParserConfig = permissive_parser_config();
+        let requirement: VersionReq = VersionReq::parse(raw_requirement, &parser_config).unwrap();
+        requirement.matches(&parse_raw_version(raw_version, &parser_config).unwrap())
+    }
+
+    #[test]
+    fn test_range() {
+        assert!(satisfies("1.5", ">=1.2, <2.0"));
+        assert!(satisfies("1.2", ">=1.2, <2.0"));
+        assert!(!satisfies("2.0", ">=1.2, <2.0"));
+        assert!(!satisfies("1.1", ">=1.2, <2.0"));
+    }
+
+    #[test]
+    fn test_assert_operators() {
+        assert!(satisfies("1.5", "=> 1.2,<< 2.0"));
+        assert!(satisfies("1.5", ">>1.4"));
+        assert!(satisfies("1.5", "!=1.4"));
+        assert!(!satisfies("1.4", "!=1.4"));
+    }
+
+    #[test]
+    fn test_short_operators() {
+        assert!(satisfies("1.5", ">1.4"));
+        assert!(satisfies("1.4", "=1.4"));
+        assert!(!satisfies("1.4", "<1.4"));
+    }
+
+    #[test]
+    fn test_no_operator_means_equal() {
+        assert!(satisfies("1.4.0", "1.4"));
+        assert!(!satisfies("1.4.1", "1.4"));
+    }
+
+    #[test]
+    fn test_prerelease_is_lower_than_release() {
+        assert!(!satisfies("2.0-rc1", ">=2.0"));
+        assert!(satisfies("2.0-rc1", "<2.0"));
+    }
+
+    #[test]
+    fn test_parse_errors_offsets() {
+        let parser_config: ParserConfig = permissive_parser_config();
+
+        assert_eq!(
+            VersionReq::parse(">=1.2, <>2.0", &parser_config).unwrap_err(),
+            ParseError::InvalidOperator { operator: "<>".to_string(), offset: 7 }
+        );
+        assert_eq!(
+            VersionReq::parse(">=1.2, < 2.x", &parser_config).unwrap_err(),
+            ParseError::InvalidLetter { letter: 'x', offset: 11 }
+        );
+        assert_eq!(
+            VersionReq::parse(">=1.2,,<2", &parser_config).unwrap_err(),
+            ParseError::EmptyComponent { offset: 6 }
+        );
+    }
+}
@@ -1,34 +1,160 @@
 use std::cmp::max;
 use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
+use super::super::errors::ParseError;
+use super::config::permissive_parser_config;
+use super::parse::parse_raw_version;
 
-#[derive(Eq)]
+
+/// A parsed version: `[epoch][separator][main chunk][char touches main chunk][suffix]`.
+#[derive(Debug, Clone, Eq)]
 pub struct Version {
-    pub epoch: Option<u8>,
+    /// Number before the epoch delimiter (`1` in `1:2.0`).
+    pub epoch: Option<Number>,
+    /// Dot-separated numbers and the optional letter touching them.
     pub main: MainBlock,
+    /// Data after the first `-` (`rc1` in `2.0-rc1`).
     pub pre_release: Option<PrereleaseBlock>,
+    /// Number after the `+` (`3` in `2.0+3`).
     pub build: Option<BuildBlock>,
 }
 
-#[derive(Eq)]
+/// Main chunk of a version, like `1.0.2` or `1.0.2e`.
+#[derive(Debug, Clone, Eq)]
 pub struct MainBlock {
-    pub numbers: Vec<u32>,
+    pub numbers: Vec<Number>,
+    /// Letter touching the last number, read as a pre-release.
     pub pre_letter: Option<char>,
+    /// Letter touching the last number, read as a post-release.
     pub post_letter: Option<char>,
+    /// Whether a `.` separates the letter from the last number, as in `6.d`.
+    /// Only used to write the version back.
+    pub dotted_letter: bool,
 }
 
-#[derive(Eq)]
+/// Pre-release suffix, like `alpha`, `rc7` or `alpha.beta`.
+#[derive(Debug, Clone, Eq)]
 pub struct PrereleaseBlock {
     pub step: String,
-    pub post_number: Option<u8>,
+    pub post_number: Option<Number>,
     pub post_step: Option<String>,
+    /// The step as written, like `RC` in `RC1`. Only used to write the version back.
+    pub written_step: String,
+    /// Whether a `.` separates the step from its number, as in `rc.3`.
+    /// Only used to write the version back.
+    pub dotted: bool,
 }
 
-#[derive(Eq)]
+/// Build number suffix.
+#[derive(Debug, Clone, Eq)]
 pub struct BuildBlock {
-    pub number: u8,
+    pub number: Number,
+}
+
+/// A run of digits of any length, like `20231015123045`.
+///
+/// The digits are kept as written, but leading zeros are ignored when
+/// comparing and hashing, so `007` and `7` are the same number.
+#[derive(Debug, Clone)]
+pub struct Number {
+    written: String,
+}
+
+
+impl Number {
+    /// The digits, without leading zeros: `0` for zero.
+    pub fn digits(&self) -> &str {
+        match self.written.trim_start_matches('0') {
+            "" => "0",
+            digits => digits,
+        }
+    }
+
+    /// The digits as written, like `05`.
+    pub fn written(&self) -> &str {
+        &self.written
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits() == "0"
+    }
+
+    /// Zero, written with as many digits as this number: `00` for `05`.
+    pub fn zeroed(&self) -> Number {
+        Number { written: "0".repeat(self.written.len()) }
+    }
+
+    /// The next number, keeping the written width: `06` after `05`, `1000` after `999`.
+    pub fn incremented(&self) -> Number {
+        let mut digits: Vec<u8> = self.written.bytes().collect();
+        let mut index: usize = digits.len();
+        loop {
+            if index == 0 {
+                digits.insert(0, b'1');
+                break
+            }
+            index -= 1;
+            if digits[index] == b'9' {
+                digits[index] = b'0';
+            } else {
+                digits[index] += 1;
+                break
+            }
+        }
+        Number { written: String::from_utf8(digits).unwrap_or_default() }
+    }
+}
+
+impl From<u64> for Number {
+    fn from(number: u64) -> Number {
+        Number { written: number.to_string() }
+    }
+}
+
+impl FromStr for Number {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Number, ParseError> {
+        if s.is_empty() {
+            return Err(ParseError::EmptyComponent { offset: 0 })
+        }
+        if let Some((index, character)) = s.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+            return Err(ParseError::UnexpectedCharacter { character, offset: index })
+        }
+        Ok(Number { written: s.to_string() })
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Number) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number {
+    /// Without leading zeros, a longer number is a larger one.
+    fn cmp(&self, other: &Number) -> Ordering {
+        let (x, y): (&str, &str) = (self.digits(), other.digits());
+        x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Number) -> bool {
+        self.digits() == other.digits()
+    }
 }
 
+impl Eq for Number {}
+
+impl Hash for Number {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.digits().hash(state);
+    }
+}
 
 impl PartialOrd for Version {
     fn partial_cmp(&self, other: &Version) -> Option<Ordering> {
@@ -62,7 +188,13 @@ impl Ord for BuildBlock {
 
 impl PartialEq for BuildBlock {
     fn eq(&self, other: &BuildBlock) -> bool {
-        self.number == other.number
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Hash for BuildBlock {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.number.hash(state);
     }
 }
 
@@ -78,59 +210,86 @@ impl Ord for Version {
             return main_order
         }
 
-        let prerelease_order: Ordering = self.cmp_prerelease(&other);
+        let prerelease_order: Ordering = self.cmp_prerelease(other);
         if prerelease_order != Ordering::Equal {
             return prerelease_order
         }
 
-        self.cmp_build(&other)
+        self.cmp_build(other)
     }
 }
 
 impl Version {
-    fn cmp_epoch(&self, other: &Version) -> Ordering {
-        match [self.epoch, other.epoch] {
+    pub(super) fn cmp_epoch(&self, other: &Version) -> Ordering {
+        match [&self.epoch, &other.epoch] {
             [None, None] => Ordering::Equal,
             [Some(_), None] => Ordering::Greater,
             [None, Some(_)] => Ordering::Less,
-            [Some(x), Some(y)] => x.cmp(&y),
+            [Some(x), Some(y)] => x.cmp(y),
         }
     }
 
-    fn cmp_prerelease(&self, other: &Version) -> Ordering {
+    pub(super) fn cmp_prerelease(&self, other: &Version) -> Ordering {
         match [&self.pre_release, &other.pre_release] {
             [None, None] => Ordering::Equal,
             [Some(_), None] => Ordering::Less,
             [None, Some(_)] => Ordering::Greater,
-            [Some(x), Some(y)] => x.cmp(&y),
+            [Some(x), Some(y)] => x.cmp(y),
         }
     }
 
-    fn cmp_build(&self, other: &Version) -> Ordering {
+    pub(super) fn cmp_build(&self, other: &Version) -> Ordering {
         match [&self.build, &other.build] {
             [None, None] => Ordering::Equal,
             [Some(_), None] => Ordering::Greater,
             [None, Some(_)] => Ordering::Less,
-            [Some(x), Some(y)] => x.cmp(&y),
+            [Some(x), Some(y)] => x.cmp(y),
         }
     }
 }
 
 impl PartialEq for Version {
     fn eq(&self, other: &Version) -> bool {
-        self.main == other.main && self.pre_release == other.pre_release
+        self.cmp(other) == Ordering::Equal
     }
 }
 
 impl PartialEq for MainBlock {
     fn eq(&self, other: &MainBlock) -> bool {
-        self.numbers == other.numbers && self.post_letter == other.post_letter
+        self.cmp(other) == Ordering::Equal
     }
 }
 
 impl PartialEq for PrereleaseBlock {
     fn eq(&self, other: &PrereleaseBlock) -> bool {
-        self.step == other.step && self.post_number == other.post_number && self.post_step == other.post_step
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Hash for Version {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.epoch.hash(state);
+        self.main.hash(state);
+        self.pre_release.hash(state);
+        self.build.hash(state);
+    }
+}
+
+impl Hash for MainBlock {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // `1` and `1.0.0` are equal: trailing zeros must not change the hash.
+        let significant_len: usize = self.numbers.iter().rposition(|n| !n.is_zero()).map_or(0, |i| i + 1);
+        self.numbers[..significant_len].hash(state);
+        self.pre_letter.hash(state);
+        self.post_letter.hash(state);
+    }
+}
+
+impl Hash for PrereleaseBlock {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.step.hash(state);
+        self.post_number.hash(state);
+        self.post_step.hash(state);
     }
 }
 
@@ -152,17 +311,11 @@ impl Ord for MainBlock {
 
 impl MainBlock {
     fn cmp_numbers(&self, other: &MainBlock) -> Ordering {
-       let default_number: u32 = 0;
+       let default_number: Number = Number::from(0);
        let max_size = max(self.numbers.len(), other.numbers.len());
        for index in 0..max_size {
-           let mut self_number = default_number;
-           let mut other_number = default_number;
-           if self.numbers.len() > index {
-               self_number = self.numbers[index];
-           }
-           if other.numbers.len() > index {
-               other_number = other.numbers[index];
-           }
+           let self_number: &Number = self.numbers.get(index).unwrap_or(&default_number);
+           let other_number: &Number = other.numbers.get(index).unwrap_or(&default_number);
 
            if self_number > other_number {
               return Ordering::Greater
@@ -173,7 +326,7 @@ impl MainBlock {
        Ordering::Equal
     }
 
-    fn cmp_pre_letter(&self, other: &MainBlock) -> Ordering {
+    pub(super) fn cmp_pre_letter(&self, other: &MainBlock) -> Ordering {
         match [self.pre_letter, other.pre_letter] {
             [None, None] => Ordering::Equal,
             [Some(_), None] => Ordering::Less,
@@ -182,7 +335,7 @@ impl MainBlock {
         }
     }
 
-    fn cmp_post_letter(&self, other: &MainBlock) -> Ordering {
+    pub(super) fn cmp_post_letter(&self, other: &MainBlock) -> Ordering {
         match [self.post_letter, other.post_letter] {
             [None, None] => Ordering::Equal,
             [Some(_), None] => Ordering::Greater,
@@ -205,12 +358,12 @@ impl Ord for PrereleaseBlock {
             return order
         }
 
-        return self.cmp_post_number(other);
+        self.cmp_post_number(other)
     }
 }
 
 impl PrereleaseBlock {
-    fn cmp_post_step(&self, other: &PrereleaseBlock) -> Ordering {
+    pub(super) fn cmp_post_step(&self, other: &PrereleaseBlock) -> Ordering {
         match [&self.post_step, &other.post_step] {
             [None, None] => Ordering::Equal,
             [Some(_), None] => Ordering::Greater,
@@ -219,27 +372,31 @@ impl PrereleaseBlock {
         }
     }
 
-    fn cmp_post_number(&self, other: &PrereleaseBlock) -> Ordering {
-        match [self.post_number, other.post_number] {
+    pub(super) fn cmp_post_number(&self, other: &PrereleaseBlock) -> Ordering {
+        match [&self.post_number, &other.post_number] {
             [None, None] => Ordering::Equal,
             [Some(_), None] => Ordering::Greater,
             [None, Some(_)] => Ordering::Less,
-            [Some(x), Some(y)] => x.cmp(&y)
+            [Some(x), Some(y)] => x.cmp(y)
         }
     }
 
-    fn cmp_step(&self, other: &PrereleaseBlock) -> Ordering {
+    pub(super) fn cmp_step(&self, other: &PrereleaseBlock) -> Ordering {
         match [self.step.len(), other.step.len()] {
-            [0, x] if x > 0 => return Ordering::Greater,
-            [x, 0] if x > 0 => return Ordering::Less,
+            [0, x] if x > 0 => Ordering::Greater,
+            [x, 0] if x > 0 => Ordering::Less,
             _ => self.step.cmp(&other.step),
         }
     }
 }
 
-#[derive(Debug, Eq)]
+/// Knobs changing how a raw version string is split.
+#[derive(Debug, Clone, Eq)]
 pub struct ParserConfig {
+    /// Character separating the epoch from the rest. `None` disables epochs.
     pub epoch_delimiter: Option<char>,
+    /// What a letter touching the main chunk means: `Some(true)` for a
+    /// pre-release, `Some(false)` for a post-release, `None` to reject it.
     pub pre_release_touchs_digit: Option<bool>,
 }
 
@@ -248,3 +405,405 @@ impl PartialEq for ParserConfig {
         self.epoch_delimiter == other.epoch_delimiter && self.pre_release_touchs_digit == other.pre_release_touchs_digit
     }
 }
+
+
+impl Version {
+    /// Parses `raw_version` according to `parser_config`.
+    pub fn parse_with(raw_version: &str, parser_config: &ParserConfig) -> Result<Version, ParseError> {
+        parse_raw_version(raw_version, parser_config)
+    }
+
+    /// Writes the version back as it was written, using the epoch delimiter
+    /// of `parser_config`.
+    ///
+    /// The output parses back to the same `Version` with `parser_config`.
+    /// Without an epoch delimiter, the epoch is left out.
+    pub fn to_string_with(&self, parser_config: &ParserConfig) -> String {
+        self.write_with(parser_config, false)
+    }
+
+    /// Like [`Version::to_string_with`], but spelled the canonical way:
+    /// numbers without leading zeros, and touching letters and `rc` numbers
+    /// without `.`. `1.01-rc.1` gives `1.1-rc1`.
+    pub fn to_normalised_string_with(&self, parser_config: &ParserConfig) -> String {
+        self.write_with(parser_config, true)
+    }
+
+    fn write_with(&self, parser_config: &ParserConfig, normalised: bool) -> String {
+        let mut s = String::new();
+        if let (Some(epoch), Some(delimiter)) = (&self.epoch, parser_config.epoch_delimiter) {
+            s.push_str(&format!("{}{}", write_number(epoch, normalised), delimiter));
+        }
+        s.push_str(&self.main.write(normalised));
+        if let Some(pre_release) = &self.pre_release {
+            s.push_str(&format!("-{}", pre_release.write(normalised)));
+        }
+        if let Some(build) = &self.build {
+            s.push_str(&format!("+{}", write_number(&build.number, normalised)));
+        }
+        s
+    }
+}
+
+impl MainBlock {
+    fn write(&self, normalised: bool) -> String {
+        let numbers: Vec<&str> = self.numbers.iter().map(|n| write_number(n, normalised)).collect();
+        let mut s: String = numbers.join(".");
+        for letter in self.pre_letter.iter().chain(self.post_letter.iter()) {
+            if self.dotted_letter && !normalised {
+                s.push('.');
+            }
+            s.push(*letter);
+        }
+        s
+    }
+}
+
+impl PrereleaseBlock {
+    fn write(&self, normalised: bool) -> String {
+        let mut s: String = match normalised {
+            true => self.step.clone(),
+            false => self.written_step.clone(),
+        };
+        if let Some(number) = &self.post_number {
+            let dotted: bool = match normalised {
+                true => self.step != "rc",
+                false => self.dotted,
+            };
+            if dotted {
+                s.push('.');
+            }
+            s.push_str(write_number(number, normalised));
+        }
+        if let Some(post_step) = &self.post_step {
+            s.push_str(&format!(".{}", post_step));
+        }
+        s
+    }
+}
+
+fn write_number(number: &Number, normalised: bool) -> &str {
+    match normalised {
+        true => number.digits(),
+        false => number.written(),
+    }
+}
+
+impl FromStr for Version {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Version, ParseError> {
+        parse_raw_version(s, &permissive_parser_config())
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string_with(&permissive_parser_config()))
+    }
+}
+
+impl fmt::Display for MainBlock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.write(false))
+    }
+}
+
+impl fmt::Display for PrereleaseBlock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.write(false))
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.written)
+    }
+}
+
+impl fmt::Display for BuildBlock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.number)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::{BTreeSet, HashSet};
+
+    use super::*;
+
+    fn python_parser_config() -> ParserConfig {
+        ParserConfig {
+            epoch_delimiter: Some('!'),
+            pre_release_touchs_digit: Some(true),
+        }
+    }
+
+    fn openssl_parser_config() -> ParserConfig {
+        ParserConfig {
+            epoch_delimiter: None,
+            pre_release_touchs_digit: Some(false),
+        }
+    }
+
+    fn assert_round_trip(raw_version: &str, expected: &str, parser_config: &ParserConfig) {
+        let version: Version = Version::parse_with(raw_version, parser_config).unwrap();
+        let written: String = version.to_string_with(parser_config);
+        let reparsed: Version = Version::parse_with(&written, parser_config).unwrap();
+
+        assert_eq!(written, expected);
+        assert_eq!(format!("{:?}", reparsed), format!("{:?}", version));
+    }
+
+    #[test]
+    fn test_round_trip_canonical_versions() {
+        let versions = [
+            "1", "2", "3", "1.0", "1.2", "2.0", "2.1", "2.0.0", "3.14159265", "5.5",
+            "1.0-alpha", "1.0-beta", "5.5-alpha", "5.5-beta", "1.0-rc1", "5.5-rc6", "3.1-rc10",
+            "5.5-alpha.2", "5.5-alpha.10", "1.0.0-alpha.1", "1.0.0-alpha.beta",
+            "1.0+1", "1.0+3", "1.0-rc1+3", "1:10", "2:2", "1:0.1", "1:1.2.3",
+        ];
+        for version in versions.iter() {
+            assert_round_trip(version, version, &permissive_parser_config());
+        }
+    }
+
+    #[test]
+    fn test_round_trip_keeps_spelling() {
+        for version in ["1.1-RC1", "5.5-rc.10", "5.5-RC.10", "2023.01.05", "1.0-alpha.007", "01:1.0+002"].iter() {
+            assert_round_trip(version, version, &permissive_parser_config());
+        }
+    }
+
+    #[test]
+    fn test_normalised_string() {
+        let normalise = |raw_version: &str| Version::parse_with(raw_version, &python_parser_config()).unwrap().to_normalised_string_with(&python_parser_config());
+
+        assert_eq!(normalise("1.01-rc.1"), "1.1-rc1");
+        assert_eq!(normalise("1.1-RC1"), "1.1-rc1");
+        assert_eq!(normalise("5.5-rc.10"), "5.5-rc10");
+        assert_eq!(normalise("01!2023.01.05-alpha.007+002"), "1!2023.1.5-alpha.7+2");
+        assert_eq!(normalise("6.d"), "6d");
+    }
+
+    #[test]
+    fn test_epoch_without_delimiter_is_left_out() {
+        let version: Version = "1:2.0".parse().unwrap();
+
+        assert_eq!(version.to_string_with(&openssl_parser_config()), "2.0");
+    }
+
+    #[test]
+    fn test_round_trip_python_epoch() {
+        assert_round_trip("1!1.2.3", "1!1.2.3", &python_parser_config());
+    }
+
+    #[test]
+    fn test_round_trip_touching_letter() {
+        assert_round_trip("1.0.2e", "1.0.2e", &openssl_parser_config());
+        assert_round_trip("1.0.2a", "1.0.2a", &python_parser_config());
+        assert_round_trip("6.d", "6.d", &python_parser_config());
+    }
+
+    #[test]
+    fn test_from_str_uses_permissive_parser_config() {
+        let version: Version = "1:2.0-rc3+4".parse().unwrap();
+
+        assert_eq!(version.to_string(), "1:2.0-rc3+4");
+        assert!("1.0.2e".parse::<Version>().is_err());
+    }
+
+    fn hash_of(version: &Version) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        version.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_epoch_is_part_of_equality() {
+        let with_epoch: Version = "1:1.0".parse().unwrap();
+        let without_epoch: Version = "1.0".parse().unwrap();
+
+        assert_ne!(with_epoch, without_epoch);
+    }
+
+    #[test]
+    fn test_build_is_part_of_equality() {
+        let with_build: Version = "1.0+1".parse().unwrap();
+        let without_build: Version = "1.0".parse().unwrap();
+
+        assert_ne!(with_build, without_build);
+    }
+
+    #[test]
+    fn test_pre_letter_is_part_of_equality() {
+        let version_a: Version = Version::parse_with("1.0a", &python_parser_config()).unwrap();
+        let version_b: Version = Version::parse_with("1.0", &python_parser_config()).unwrap();
+
+        assert_ne!(version_a, version_b);
+    }
+
+    #[test]
+    fn test_trailing_zeros_are_equal_and_hash_equal() {
+        let short: Version = "1".parse().unwrap();
+        let long: Version = "1.0.0".parse().unwrap();
+
+        assert_eq!(short, long);
+        assert_eq!(hash_of(&short), hash_of(&long));
+    }
+
+    #[test]
+    fn test_number_ignores_leading_zeros() {
+        let number: Number = "007".parse().unwrap();
+
+        assert_eq!(number, Number::from(7));
+        assert_eq!(hash_of(&"1.007".parse().unwrap()), hash_of(&"1.7".parse().unwrap()));
+        assert_eq!(number.digits(), "7");
+        assert_eq!(number.to_string(), "007");
+        assert_eq!("000".parse::<Number>().unwrap(), Number::from(0));
+        assert_eq!("".parse::<Number>(), Err(ParseError::EmptyComponent { offset: 0 }));
+        assert_eq!("1x".parse::<Number>(), Err(ParseError::UnexpectedCharacter { character: 'x', offset: 1 }));
+    }
+
+    #[test]
+    fn test_number_incremented() {
+        assert_eq!(Number::from(0).incremented(), Number::from(1));
+        assert_eq!(Number::from(1299).incremented(), Number::from(1300));
+        assert_eq!(Number::from(u64::MAX).incremented().digits(), "18446744073709551616");
+        assert_eq!("05".parse::<Number>().unwrap().incremented().to_string(), "06");
+        assert_eq!("09".parse::<Number>().unwrap().incremented().to_string(), "10");
+        assert_eq!("99".parse::<Number>().unwrap().incremented().to_string(), "100");
+    }
+
+    #[test]
+    fn test_number_order_beyond_u64() {
+        let numbers: Vec<Number> = ["9", "18446744073709551615", "18446744073709551616", "0099999999999999999999999", "100000000000000000000000"]
+            .iter().map(|n| n.parse().unwrap()).collect();
+
+        for pair in numbers.windows(2) {
+            assert!(pair[0] < pair[1], "{} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn test_collections_agree() {
+        let raw_versions = ["1", "1.0", "1.0.0", "1:1", "1.0+1", "1.0-rc1", "1.1-RC1", "1.1-rc1"];
+        let versions: Vec<Version> = raw_versions.iter().map(|v| v.parse().unwrap()).collect();
+
+        let tree: BTreeSet<&Version> = versions.iter().collect();
+        let hash: HashSet<&Version> = versions.iter().collect();
+
+        assert_eq!(tree.len(), 5);
+        assert_eq!(hash.len(), 5);
+    }
+
+    /// Deterministic xorshift generator, so failures can be replayed.
+    struct Generator(u64);
+
+    impl Generator {
+        fn below(&mut self, bound: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % bound
+        }
+
+        fn maybe<T>(&mut self, f: impl FnOnce(&mut Generator) -> T) -> Option<T> {
+            match self.below(2) {
+                0 => None,
+                _ => Some(f(self)),
+            }
+        }
+
+        fn pick(&mut self, values: &[&str]) -> String {
+            values[self.below(values.len() as u64) as usize].to_string()
+        }
+
+        fn version(&mut self) -> Version {
+            let numbers_len = self.below(4);
+            Version {
+                epoch: self.maybe(|g| Number::from(g.below(3))),
+                main: MainBlock {
+                    numbers: (0..numbers_len).map(|_| Number::from(self.below(3))).collect(),
+                    pre_letter: self.maybe(|g| if g.below(2) == 0 { 'a' } else { 'b' }),
+                    post_letter: self.maybe(|g| if g.below(2) == 0 { 'a' } else { 'b' }),
+                    dotted_letter: false,
+                },
+                pre_release: self.maybe(|g| {
+                    let step: String = g.pick(&["", "alpha", "beta", "rc"]);
+                    PrereleaseBlock {
+                        written_step: step.clone(),
+                        dotted: step != "rc",
+                        step,
+                        post_number: g.maybe(|g| Number::from(g.below(3))),
+                        post_step: g.maybe(|g| g.pick(&["alpha", "beta"])),
+                    }
+                }),
+                build: self.maybe(|g| BuildBlock { number: Number::from(g.below(3)) }),
+            }
+        }
+
+        fn versions(&mut self, quantity: usize) -> Vec<Version> {
+            (0..quantity).map(|_| self.version()).collect()
+        }
+    }
+
+    #[test]
+    fn test_property_reflexive() {
+        for version in Generator(0x5eed).versions(500).iter() {
+            assert_eq!(version.cmp(version), Ordering::Equal, "{:?}", version);
+            assert_eq!(version, version);
+        }
+    }
+
+    #[test]
+    fn test_property_antisymmetric() {
+        let versions: Vec<Version> = Generator(0xa5a5).versions(200);
+        for a in versions.iter() {
+            for b in versions.iter() {
+                assert_eq!(a.cmp(b), b.cmp(a).reverse(), "{:?} {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_property_transitive() {
+        let versions: Vec<Version> = Generator(0xbeef).versions(60);
+        for a in versions.iter() {
+            for b in versions.iter() {
+                for c in versions.iter() {
+                    if a <= b && b <= c {
+                        assert!(a <= c, "{:?} {:?} {:?}", a, b, c);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_property_eq_agrees_with_ord_and_hash() {
+        let versions: Vec<Version> = Generator(0xcafe).versions(200);
+        for a in versions.iter() {
+            for b in versions.iter() {
+                assert_eq!(a == b, a.cmp(b) == Ordering::Equal, "{:?} {:?}", a, b);
+                assert_eq!(a.partial_cmp(b), Some(a.cmp(b)), "{:?} {:?}", a, b);
+                if a == b {
+                    assert_eq!(hash_of(a), hash_of(b), "{:?} {:?}", a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_property_sets_agree() {
+        let versions: Vec<Version> = Generator(0xf00d).versions(500);
+
+        let tree: BTreeSet<&Version> = versions.iter().collect();
+        let hash: HashSet<&Version> = versions.iter().collect();
+
+        assert_eq!(tree.len(), hash.len());
+    }
+}
@@ -1,5 +1,7 @@
 use std::cmp::Ordering;
 
+use cvers::{Number, Version};
+
 
 pub fn display(order: Ordering)-> String{
     match order {
@@ -9,6 +11,64 @@ pub fn display(order: Ordering)-> String{
     }
 }
 
+/// How `version` was split, one component per line.
+pub fn table(version: &Version) -> String {
+    let text = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    let pre_release = version.pre_release.as_ref();
+    let numbers: Vec<String> = version.main.numbers.iter().map(Number::to_string).collect();
+    let rows: [(&str, String); 8] = [
+        ("epoch", text(version.epoch.as_ref().map(Number::to_string))),
+        ("numbers", numbers.join(" ")),
+        ("pre_letter", text(version.main.pre_letter.map(String::from))),
+        ("post_letter", text(version.main.post_letter.map(String::from))),
+        ("step", text(pre_release.map(|p| p.step.clone()))),
+        ("post_number", text(pre_release.and_then(|p| p.post_number.as_ref()).map(Number::to_string))),
+        ("post_step", text(pre_release.and_then(|p| p.post_step.clone()))),
+        ("build", text(version.build.as_ref().map(|b| b.number.to_string()))),
+    ];
+    let lines: Vec<String> = rows.iter().map(|(name, value)| format!("{:<12} {}", name, value)).collect();
+    lines.join("\n")
+}
+
+/// How `version` was split, as a JSON object. Missing components are `null`.
+pub fn json(version: &Version) -> String {
+    // JSON numbers can not have leading zeros.
+    let number = |value: Option<&Number>| value.map_or("null".to_string(), |n| n.digits().to_string());
+    let string = |value: Option<String>| value.map_or("null".to_string(), |v| json_string(&v));
+    let numbers: Vec<String> = version.main.numbers.iter().map(|n| n.digits().to_string()).collect();
+    let main: String = format!(
+        "{{\"numbers\": [{}], \"pre_letter\": {}, \"post_letter\": {}}}",
+        numbers.join(", "),
+        string(version.main.pre_letter.map(String::from)),
+        string(version.main.post_letter.map(String::from)),
+    );
+    let pre_release: String = match &version.pre_release {
+        Some(p) => format!(
+            "{{\"step\": {}, \"post_number\": {}, \"post_step\": {}}}",
+            json_string(&p.step), number(p.post_number.as_ref()), string(p.post_step.clone()),
+        ),
+        None => "null".to_string(),
+    };
+    format!(
+        "{{\"epoch\": {}, \"main\": {}, \"pre_release\": {}, \"build\": {}}}",
+        number(version.epoch.as_ref()), main, pre_release, number(version.build.as_ref().map(|b| &b.number)),
+    )
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped: String = String::from("\"");
+    for character in value.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -26,4 +86,29 @@ mod tests {
     fn test_display_sup() {
         assert_eq!(display(Ordering::Greater), ">".to_string());
     }
+    #[test]
+    fn test_table() {
+        let version: Version = "1:2.0-rc3+4".parse().unwrap();
+        let expected: &str = "epoch        1
+numbers      2 0
+pre_letter   -
+post_letter  -
+step         rc
+post_number  3
+post_step    -
+build        4";
+        assert_eq!(table(&version), expected);
+    }
+    #[test]
+    fn test_json() {
+        let version: Version = "1.0-alpha.\"b\\".parse().unwrap();
+        let expected: &str = r#"{"epoch": null, "main": {"numbers": [1, 0], "pre_letter": null, "post_letter": null}, "pre_release": {"step": "alpha", "post_number": null, "post_step": "\"b\\"}, "build": null}"#;
+        assert_eq!(json(&version), expected);
+    }
+    #[test]
+    fn test_json_numbers_without_leading_zeros() {
+        let version: Version = "2023.01.05-rc.03".parse().unwrap();
+        let expected: &str = r#"{"epoch": null, "main": {"numbers": [2023, 1, 5], "pre_letter": null, "post_letter": null}, "pre_release": {"step": "rc", "post_number": 3, "post_step": null}, "build": null}"#;
+        assert_eq!(json(&version), expected);
+    }
 }
@@ -1,6 +1,116 @@
-use std::process;
+use std::error::Error;
+use std::fmt;
 
-pub fn exit_on_error(message: &str) -> ! {
-        eprintln!("{}", message);
-        process::exit(2);
+/// Why a raw version string could not be parsed.
+///
+/// Every variant carries the byte offset, in the raw string, where the
+/// problem was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A character that cannot appear at this place, like `x` in `1.x.3`.
+    UnexpectedCharacter { character: char, offset: usize },
+    /// A number too large for its scheme, like a semver component above `u64::MAX`.
+    NumericOverflow { offset: usize },
+    /// A delimiter found more than once, like the second `:` in `1:2:3`.
+    DuplicateDelimiter { delimiter: char, offset: usize },
+    /// Nothing between two delimiters, like in `1..3`, or a missing component.
+    EmptyComponent { offset: usize },
+    /// A number written with a leading zero where the scheme forbids it, like `01` in semver.
+    LeadingZero { offset: usize },
+    /// A letter touching the main chunk while the parser does not accept it.
+    InvalidLetter { letter: char, offset: usize },
+    /// An unknown comparison operator, like `<>`.
+    InvalidOperator { operator: String, offset: usize },
+    /// A range no version can be in, like `[2.0,1.0]`.
+    EmptyRange { offset: usize },
+    /// A marker variable that is not defined, like `python_versoin`.
+    UnknownVariable { name: String, offset: usize },
+}
+
+impl ParseError {
+    pub fn offset(&self) -> usize {
+        match *self {
+            ParseError::UnexpectedCharacter { offset, .. } => offset,
+            ParseError::NumericOverflow { offset } => offset,
+            ParseError::DuplicateDelimiter { offset, .. } => offset,
+            ParseError::EmptyComponent { offset } => offset,
+            ParseError::LeadingZero { offset } => offset,
+            ParseError::InvalidLetter { offset, .. } => offset,
+            ParseError::InvalidOperator { offset, .. } => offset,
+            ParseError::EmptyRange { offset } => offset,
+            ParseError::UnknownVariable { offset, .. } => offset,
+        }
+    }
+
+    /// Moves the offset by `shift` bytes, for a string parsed from inside a larger one.
+    pub(crate) fn shifted(self, shift: usize) -> ParseError {
+        match self {
+            ParseError::UnexpectedCharacter { character, offset } => ParseError::UnexpectedCharacter { character, offset: offset + shift },
+            ParseError::NumericOverflow { offset } => ParseError::NumericOverflow { offset: offset + shift },
+            ParseError::DuplicateDelimiter { delimiter, offset } => ParseError::DuplicateDelimiter { delimiter, offset: offset + shift },
+            ParseError::EmptyComponent { offset } => ParseError::EmptyComponent { offset: offset + shift },
+            ParseError::LeadingZero { offset } => ParseError::LeadingZero { offset: offset + shift },
+            ParseError::InvalidLetter { letter, offset } => ParseError::InvalidLetter { letter, offset: offset + shift },
+            ParseError::InvalidOperator { operator, offset } => ParseError::InvalidOperator { operator, offset: offset + shift },
+            ParseError::EmptyRange { offset } => ParseError::EmptyRange { offset: offset + shift },
+            ParseError::UnknownVariable { name, offset } => ParseError::UnknownVariable { name, offset: offset + shift },
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedCharacter { character, offset } => write!(
+                f, "Error: unexpected character '{}' at position {}", character, offset),
+            ParseError::NumericOverflow { offset } => write!(
+                f, "Error: number too large at position {}", offset),
+            ParseError::DuplicateDelimiter { delimiter, offset } => write!(
+                f, "Error: more than one '{}' character at position {}", delimiter, offset),
+            ParseError::EmptyComponent { offset } => write!(
+                f, "Error: empty component at position {}", offset),
+            ParseError::LeadingZero { offset } => write!(
+                f, "Error: leading zero at position {}", offset),
+            ParseError::InvalidLetter { letter, offset } => write!(
+                f, "Error: invalid letter ('{}') at position {}", letter, offset),
+            ParseError::InvalidOperator { operator, offset } => write!(
+                f, "Error: invalid operator '{}' at position {}", operator, offset),
+            ParseError::EmptyRange { offset } => write!(
+                f, "Error: empty range at position {}", offset),
+            ParseError::UnknownVariable { name, offset } => write!(
+                f, "Error: unknown variable '{}' at position {}", name, offset),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offset() {
+        assert_eq!(ParseError::EmptyComponent { offset: 3 }.offset(), 3);
+        assert_eq!(ParseError::InvalidLetter { letter: 'a', offset: 4 }.offset(), 4);
+    }
+
+    #[test]
+    fn test_shifted() {
+        let error = ParseError::UnexpectedCharacter { character: 'x', offset: 2 };
+        assert_eq!(error.shifted(3), ParseError::UnexpectedCharacter { character: 'x', offset: 5 });
+    }
+
+    #[test]
+    fn test_display_duplicate_delimiter() {
+        let error = ParseError::DuplicateDelimiter { delimiter: ':', offset: 3 };
+        assert_eq!(error.to_string(), "Error: more than one ':' character at position 3");
+    }
+
+    #[test]
+    fn test_display_unexpected_character() {
+        let error = ParseError::UnexpectedCharacter { character: 'x', offset: 2 };
+        assert_eq!(error.to_string(), "Error: unexpected character 'x' at position 2");
+    }
 }
@@ -0,0 +1,46 @@
+//! Compare VERSion numbers.
+//!
+//! `cvers` parses version strings such as `1:2.0.1-rc3+4` into a [`Version`]
+//! and orders them. The same functions back the `cvers` command line tool.
+//!
+//! ```
+//! use std::cmp::Ordering;
+//!
+//! let config = cvers::permissive_parser_config();
+//! assert_eq!(cvers::compare("1.0", "1.1", &config), Ok(Ordering::Less));
+//! assert_eq!(cvers::compare_with_operator("1.1", "1.2", "<<", &config), Ok(true));
+//! assert!(cvers::compare("1.x.3", "1.0", &config).is_err());
+//! ```
+//!
+//! Each versioning ecosystem has its own [`VersionScheme`]:
+//!
+//! ```
+//! use std::cmp::Ordering;
+//! use cvers::VersionScheme;
+//!
+//! assert_eq!(cvers::DebianScheme.compare("1.0~rc1", "1.0"), Ok(Ordering::Less));
+//! assert_eq!(cvers::Pep440Scheme.normalise("1.0-ALPHA-1"), Ok("1.0a1".to_string()));
+//! ```
+
+mod compare;
+mod errors;
+mod ranges;
+mod schemes;
+
+pub use compare::compare;
+pub use compare::compare_with_operator;
+pub use compare::compare_detailed;
+pub use compare::{Comparison, Component};
+pub use compare::try_compare;
+pub use compare::parse_raw_version;
+pub use compare::{Comparator, Operator, VersionReq};
+pub use compare::permissive_parser_config;
+pub use errors::ParseError;
+pub use ranges::{Dependency, InstalledPackages, Relation, RelationField, RelationOperator};
+pub use ranges::{is_interval_range, Bound, Interval, IntervalRange};
+pub use ranges::{Marker, MarkerEnvironment, MarkerOperator, MarkerValue};
+pub use ranges::{CargoComparator, CargoOperator, CargoReq, NpmComparator, NpmRange, Specifier, SpecifierOperator, SpecifierSet};
+pub use schemes::{Scheme, VersionScheme};
+pub use schemes::{DebianScheme, MavenScheme, NpmScheme, Pep440Scheme, PermissiveScheme, RpmScheme, RubygemsScheme, SemverScheme};
+pub use schemes::{DebianVersion, GemSegment, GemVersion, Identifier, Item, LocalSegment, MavenVersion, Pep440Version, PreReleaseKind, RpmVersion, SemVer};
+pub use compare::{BuildBlock, MainBlock, Number, ParserConfig, Part, PrereleaseBlock, Version};
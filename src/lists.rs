@@ -0,0 +1,143 @@
+use std::cmp::Ordering;
+
+use cvers::{ParserConfig, Version, VersionReq};
+
+
+/// Sorts the version lines of `sources`, given as `(name, content)` pairs.
+///
+/// Returns the sorted lines, as written, and one `name:line: error` message
+/// per line that does not parse. Blank lines are skipped. Equal versions keep
+/// their order, even with `reverse`; `unique` keeps the first of them.
+pub fn sort_lines(sources: &[(&str, &str)], reverse: bool, unique: bool, parser_config: &ParserConfig) -> (Vec<String>, Vec<String>) {
+    let mut versions: Vec<(Version, &str)> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+    for (source, content) in sources.iter() {
+        for (index, line) in content.lines().enumerate() {
+            let line: &str = line.trim();
+            if line.is_empty() {
+                continue
+            }
+            match Version::parse_with(line, parser_config) {
+                Ok(version) => versions.push((version, line)),
+                Err(error) => errors.push(format!("{source}:{line_number}: {error}", source=source, line_number=index + 1, error=error)),
+            }
+        }
+    }
+
+    versions.sort_by(|(a, _), (b, _)| match reverse {
+        false => a.cmp(b),
+        true => b.cmp(a),
+    });
+    if unique {
+        versions.dedup_by(|(a, _), (b, _)| a == b);
+    }
+    let lines: Vec<String> = versions.iter().map(|(_, line)| line.to_string()).collect();
+    (lines, errors)
+}
+
+/// Picks the greatest (`wanted` is `Greater`) or least of `candidates`.
+///
+/// Only the versions matching `requirement`, and without pre-release with
+/// `exclude_prerelease`, are candidates. Among equal versions, the first one
+/// wins. Returns the winner, as written, and one `'candidate': error`
+/// message per candidate that does not parse.
+pub fn extremum(candidates: &[&str], wanted: Ordering, requirement: Option<&VersionReq>, exclude_prerelease: bool, parser_config: &ParserConfig) -> (Option<String>, Vec<String>) {
+    let mut best: Option<(Version, &str)> = None;
+    let mut errors: Vec<String> = Vec::new();
+    for candidate in candidates.iter() {
+        let version: Version = match Version::parse_with(candidate, parser_config) {
+            Ok(version) => version,
+            Err(error) => {
+                errors.push(format!("'{candidate}': {error}", candidate=candidate, error=error));
+                continue
+            },
+        };
+        if exclude_prerelease && version.pre_release.is_some() {
+            continue
+        }
+        if requirement.is_some_and(|requirement| !requirement.matches(&version)) {
+            continue
+        }
+        if best.as_ref().is_none_or(|(best, _)| version.cmp(best) == wanted) {
+            best = Some((version, candidate));
+        }
+    }
+    (best.map(|(_, candidate)| candidate.to_string()), errors)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cvers::permissive_parser_config;
+
+    const CANDIDATES: [&str; 6] = ["1.2", "2.0-rc1", "1.10", "0.9", "1.10.0", "x"];
+
+    fn sort(content: &str, reverse: bool, unique: bool) -> (Vec<String>, Vec<String>) {
+        sort_lines(&[("stdin", content)], reverse, unique, &permissive_parser_config())
+    }
+
+    #[test]
+    fn test_sort_lines() {
+        let (lines, errors) = sort("1.10\n1.2\n\n1.0-rc1\n", false, false);
+        assert_eq!(lines, vec!["1.0-rc1", "1.2", "1.10"]);
+        assert!(errors.is_empty());
+    }
+    #[test]
+    fn test_sort_lines_reverse_keeps_equal_order() {
+        let (lines, _) = sort("1.2\n1.10\n1.2.0\n", true, false);
+        assert_eq!(lines, vec!["1.10", "1.2", "1.2.0"]);
+    }
+    #[test]
+    fn test_sort_lines_unique_keeps_first() {
+        let (lines, _) = sort("1.2.0\n1.10\n1.2\n", false, true);
+        assert_eq!(lines, vec!["1.2.0", "1.10"]);
+    }
+    #[test]
+    fn test_sort_lines_reports_errors() {
+        let (lines, errors) = sort_lines(&[("a.txt", "1.2\nlatest\n"), ("b.txt", " 1.0 \n1.x\n")], false, false, &permissive_parser_config());
+        assert_eq!(lines, vec!["1.0", "1.2"]);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("a.txt:2: "), "{}", errors[0]);
+        assert!(errors[1].starts_with("b.txt:2: "), "{}", errors[1]);
+    }
+    #[test]
+    fn test_extremum_max_and_min() {
+        let (max, errors) = extremum(&CANDIDATES, Ordering::Greater, None, false, &permissive_parser_config());
+        assert_eq!(max, Some("2.0-rc1".to_string()));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("'x': "), "{}", errors[0]);
+
+        let (min, _) = extremum(&CANDIDATES, Ordering::Less, None, false, &permissive_parser_config());
+        assert_eq!(min, Some("0.9".to_string()));
+    }
+    #[test]
+    fn test_extremum_satisfying() {
+        let requirement: VersionReq = VersionReq::parse(">=1.0, <1.10", &permissive_parser_config()).unwrap();
+        let (max, _) = extremum(&CANDIDATES, Ordering::Greater, Some(&requirement), false, &permissive_parser_config());
+        assert_eq!(max, Some("1.2".to_string()));
+    }
+    #[test]
+    fn test_extremum_exclude_prerelease() {
+        let (max, _) = extremum(&CANDIDATES, Ordering::Greater, None, true, &permissive_parser_config());
+        assert_eq!(max, Some("1.10".to_string()));
+    }
+    #[test]
+    fn test_extremum_first_of_equals_wins() {
+        let (max, _) = extremum(&["1.10.0", "1.10", "1.2"], Ordering::Greater, None, false, &permissive_parser_config());
+        assert_eq!(max, Some("1.10.0".to_string()));
+
+        let (min, _) = extremum(&["1.2", "1.2.0", "1.10"], Ordering::Less, None, false, &permissive_parser_config());
+        assert_eq!(min, Some("1.2".to_string()));
+    }
+    #[test]
+    fn test_extremum_everything_filtered_out() {
+        let requirement: VersionReq = VersionReq::parse(">=3", &permissive_parser_config()).unwrap();
+        let (max, _) = extremum(&CANDIDATES, Ordering::Greater, Some(&requirement), false, &permissive_parser_config());
+        assert_eq!(max, None);
+
+        let (min, errors) = extremum(&["2.0-rc1", "x"], Ordering::Less, None, true, &permissive_parser_config());
+        assert_eq!(min, None);
+        assert_eq!(errors.len(), 1);
+    }
+}
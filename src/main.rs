@@ -1,74 +1,466 @@
 use std::env;
+use std::fs;
+use std::io::{self, Read};
 use std::process;
 
+use std::cmp::Ordering;
 use std::collections::HashSet;
 
+use cvers::{CargoReq, GemVersion, InstalledPackages, IntervalRange, Marker, MarkerEnvironment, NpmRange, NpmScheme, Operator, ParserConfig, Part, Relation, RelationField, Scheme, SemVer, SpecifierSet, Version, VersionReq, VersionScheme};
+
 mod args;
-mod compare;
 mod display;
-mod errors;
+mod lists;
 
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() == 1 {
-        errors::exit_on_error("Missing parameters");
+        exit_on_error("Missing parameters");
     } else if args[1] == "--help" {
         help();
-    } else if args.len() < 4 {
-        errors::exit_on_error("Missing parameters");
     } else {
         let tuple = args::parse_arguments(args);
-        let config: compare::ParserConfig = tuple.0;
-        let mandatories_args: Vec<String> = tuple.1;
-        canonical_operations(config, mandatories_args);
+        let config: ParserConfig = tuple.0;
+        let scheme: Scheme = tuple.1;
+        let mandatories_args: Vec<String> = tuple.2;
+        let minimum: usize = match mandatories_args.get(1).map(String::as_str) {
+            Some("sort") | Some("max") | Some("min") => 2,
+            Some("parse") => 3,
+            Some("assert") => 5,
+            _ => 4,
+        };
+        if mandatories_args.len() < minimum {
+            exit_on_error("Missing parameters");
+        }
+        canonical_operations(config, scheme, mandatories_args);
     }
 }
 
+fn exit_on_error(message: &str) -> ! {
+        eprintln!("{}", message);
+        process::exit(2);
+}
+
 fn help() {
     println!("Usage:
- - cvers compare version1 version2
+ - cvers compare [--explain] version1 version2
  - cvers assert version1 operator version2
+ - cvers satisfies version requirement
+ - cvers deps-check status_file relations
+ - cvers marker expression environment...
+ - cvers sort [--reverse] [--unique] [file...]
+ - cvers max|min [--satisfying requirement] [--exclude-prerelease] [version...]
+ - cvers bump major|minor|patch|pre|build|epoch version
+ - cvers parse [--format table|json] version
  - cvers --help");
 }
 
-fn canonical_operations(parser_config: compare::ParserConfig, args: Vec<String>) {
+fn canonical_operations(parser_config: ParserConfig, scheme: Scheme, args: Vec<String>) {
     let verb = args[1].as_str();
     match verb {
         "compare" => {
-            compare_operation(&args[2], &args[3], parser_config);
+            let explain: bool = args[2..].iter().any(|parameter| parameter == "--explain");
+            let versions: Vec<&String> = args[2..].iter().filter(|parameter| *parameter != "--explain").collect();
+            if versions.len() < 2 {
+                exit_on_error("Missing parameters");
+            }
+            if explain {
+                if scheme != Scheme::Permissive {
+                    exit_on_error("The '--explain' option only supports the permissive scheme.");
+                }
+                explain_operation(versions[0], versions[1], parser_config);
+            } else {
+                compare_operation(versions[0], versions[1], scheme, parser_config);
+            }
         },
         "assert" => {
             let operator = &args[3];
-            let operators: HashSet<&'static str> = ["<<", "<=", "==", "=>", ">>", "!="].iter().cloned().collect();
+            let operators: HashSet<&'static str> = ["<<", "<=", "==", "=>", ">>", "!=", "~>"].iter().cloned().collect();
             if ! operators.contains(&operator.as_str()) {
                 let error_message = format!("Invalid operator '{operator}'.", operator=operator);
-                errors::exit_on_error(error_message.as_str());
+                exit_on_error(error_message.as_str());
+            } else if operator == "~>" {
+                if scheme != Scheme::Rubygems {
+                    exit_on_error("The '~>' operator only supports the rubygems scheme.");
+                }
+                process::exit(
+                    pessimistic_operation(&args[2], &args[4])
+                );
             } else {
                 process::exit(
-                    assert_operation(&args[2], &args[4], operator, parser_config)
+                    assert_operation(&args[2], &args[4], operator, scheme, parser_config)
+                );
+            }
+        },
+        "satisfies" => {
+            if cvers::is_interval_range(&args[3]) {
+                process::exit(
+                    interval_satisfies_operation(&args[2], &args[3], scheme, parser_config)
                 );
             }
+            match scheme {
+                Scheme::Permissive => process::exit(
+                    satisfies_operation(&args[2], &args[3], parser_config)
+                ),
+                Scheme::Npm => process::exit(
+                    npm_satisfies_operation(&args[2], &args[3])
+                ),
+                Scheme::Cargo => process::exit(
+                    cargo_satisfies_operation(&args[2], &args[3])
+                ),
+                Scheme::Pep440 => process::exit(
+                    pep440_satisfies_operation(&args[2], &args[3])
+                ),
+                _ => exit_on_error("The 'satisfies' verb only supports the permissive, npm, cargo and pep440 schemes, or an interval range."),
+            }
+        },
+        "deps-check" => {
+            process::exit(
+                deps_check_operation(&args[2], &args[3])
+            );
+        },
+        "sort" => {
+            if scheme != Scheme::Permissive {
+                exit_on_error("The 'sort' verb only supports the permissive scheme.");
+            }
+            process::exit(
+                sort_operation(&args[2..], parser_config)
+            );
+        },
+        "max" | "min" => {
+            if scheme != Scheme::Permissive {
+                let error_message = format!("The '{verb}' verb only supports the permissive scheme.", verb=verb);
+                exit_on_error(error_message.as_str());
+            }
+            let wanted: Ordering = match verb {
+                "max" => Ordering::Greater,
+                _ => Ordering::Less,
+            };
+            process::exit(
+                extremum_operation(&args[2..], wanted, parser_config)
+            );
+        },
+        "bump" => {
+            if scheme != Scheme::Permissive {
+                exit_on_error("The 'bump' verb only supports the permissive scheme.");
+            }
+            bump_operation(&args[2], &args[3], parser_config);
+        },
+        "parse" => {
+            if scheme != Scheme::Permissive {
+                exit_on_error("The 'parse' verb only supports the permissive scheme.");
+            }
+            parse_operation(&args[2..], parser_config);
+        },
+        "marker" => {
+            process::exit(
+                marker_operation(&args[2], &args[3..])
+            );
         },
         _ => {
-            let error_message = format!("Invalid verb '{verb}'. Use 'compare' or 'assert'.", verb=verb);
-            errors::exit_on_error(error_message.as_str());
+            let error_message = format!("Invalid verb '{verb}'. Use 'compare', 'assert', 'satisfies', 'deps-check', 'marker', 'sort', 'max', 'min', 'bump' or 'parse'.", verb=verb);
+            exit_on_error(error_message.as_str());
         }
     }
 }
 
-fn compare_operation(version_a: &str, version_b: &str, parser_config: compare::ParserConfig) {
-    println!("{}", display::display(
-            compare::compare(version_a, version_b, &parser_config)
-        )
-    );
+fn compare_operation(version_a: &str, version_b: &str, scheme: Scheme, parser_config: ParserConfig) {
+    match scheme.try_compare(version_a, version_b, &parser_config) {
+        Ok(Some(order)) => println!("{}", display::display(order)),
+        Ok(None) => {
+            println!("?");
+            process::exit(3);
+        },
+        Err(error) => exit_on_error(&error.to_string()),
+    }
+}
+
+fn explain_operation(version_a: &str, version_b: &str, parser_config: ParserConfig) {
+    match cvers::compare_detailed(version_a, version_b, &parser_config) {
+        Ok(comparison) => {
+            println!("{}", display::display(comparison.ordering));
+            println!("{}", comparison);
+        },
+        Err(error) => exit_on_error(&error.to_string()),
+    }
+}
+
+fn assert_operation(version_a: &str, version_b: &str, operator: &str, scheme: Scheme, parser_config: ParserConfig) -> i32 {
+    let operator: Operator = match operator.parse() {
+        Ok(operator) => operator,
+        Err(error) => exit_on_error(&error.to_string()),
+    };
+    let order = scheme.compare(version_a, version_b, &parser_config);
+    match order.map(|order| operator.matches(order)) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(error) => exit_on_error(&error.to_string()),
+    }
+}
+
+fn pessimistic_operation(raw_version: &str, raw_requirement: &str) -> i32 {
+    let version: GemVersion = match GemVersion::parse(raw_version) {
+        Ok(version) => version,
+        Err(error) => exit_on_error(&error.to_string()),
+    };
+    let requirement: GemVersion = match GemVersion::parse(raw_requirement) {
+        Ok(requirement) => requirement,
+        Err(error) => exit_on_error(&error.to_string()),
+    };
+    match version.matches_pessimistic(&requirement) {
+        true => 0,
+        false => 1,
+    }
+}
+
+fn satisfies_operation(raw_version: &str, raw_requirement: &str, parser_config: ParserConfig) -> i32 {
+    let version: Version = match cvers::parse_raw_version(raw_version, &parser_config) {
+        Ok(version) => version,
+        Err(error) => exit_on_error(&error.to_string()),
+    };
+    let requirement: VersionReq = match VersionReq::parse(raw_requirement, &parser_config) {
+        Ok(requirement) => requirement,
+        Err(error) => exit_on_error(&error.to_string()),
+    };
+    match requirement.matches(&version) {
+        true => 0,
+        false => 1,
+    }
+}
+
+fn npm_satisfies_operation(raw_version: &str, raw_range: &str) -> i32 {
+    let version: SemVer = match NpmScheme.parse(raw_version) {
+        Ok(version) => version,
+        Err(error) => exit_on_error(&error.to_string()),
+    };
+    let range: NpmRange = match NpmRange::parse(raw_range) {
+        Ok(range) => range,
+        Err(error) => exit_on_error(&error.to_string()),
+    };
+    match range.matches(&version) {
+        true => 0,
+        false => 1,
+    }
+}
+
+fn cargo_satisfies_operation(raw_version: &str, raw_requirement: &str) -> i32 {
+    let version: SemVer = match SemVer::parse(raw_version) {
+        Ok(version) => version,
+        Err(error) => exit_on_error(&error.to_string()),
+    };
+    let requirement: CargoReq = match CargoReq::parse(raw_requirement) {
+        Ok(requirement) => requirement,
+        Err(error) => exit_on_error(&error.to_string()),
+    };
+    match requirement.matches(&version) {
+        true => 0,
+        false => 1,
+    }
+}
+
+fn pep440_satisfies_operation(raw_version: &str, raw_specifiers: &str) -> i32 {
+    let specifiers: SpecifierSet = match SpecifierSet::parse(raw_specifiers) {
+        Ok(specifiers) => specifiers,
+        Err(error) => exit_on_error(&error.to_string()),
+    };
+    match specifiers.contains_str(raw_version) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(error) => exit_on_error(&error.to_string()),
+    }
+}
+
+fn interval_satisfies_operation(raw_version: &str, raw_range: &str, scheme: Scheme, parser_config: ParserConfig) -> i32 {
+    let compare = |a: &str, b: &str| scheme.compare(a, b, &parser_config);
+    let range: IntervalRange = match IntervalRange::parse(raw_range, compare) {
+        Ok(range) => range,
+        Err(error) => exit_on_error(&error.to_string()),
+    };
+    match range.contains(raw_version, compare) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(error) => exit_on_error(&error.to_string()),
+    }
+}
+
+fn deps_check_operation(status_path: &str, raw_relations: &str) -> i32 {
+    let status: String = match fs::read_to_string(status_path) {
+        Ok(status) => status,
+        Err(error) => exit_on_error(&format!("Cannot read '{path}': {error}", path=status_path, error=error)),
+    };
+    let installed: InstalledPackages = match InstalledPackages::parse_dpkg_status(&status) {
+        Ok(installed) => installed,
+        Err(error) => exit_on_error(&format!("{error} in '{path}'", error=error, path=status_path)),
+    };
+    let relations: RelationField = match RelationField::parse(raw_relations) {
+        Ok(relations) => relations,
+        Err(error) => exit_on_error(&error.to_string()),
+    };
+    let unsatisfied: Vec<&Relation> = relations.unsatisfied(&installed);
+    for relation in unsatisfied.iter() {
+        println!("{}", relation);
+    }
+    match unsatisfied.is_empty() {
+        true => 0,
+        false => 1,
+    }
+}
+
+/// Each source is a JSON object, a `name=value` pair or a file holding a JSON object.
+fn marker_operation(raw_marker: &str, environment_sources: &[String]) -> i32 {
+    let marker: Marker = match Marker::parse(raw_marker) {
+        Ok(marker) => marker,
+        Err(error) => exit_on_error(&error.to_string()),
+    };
+    let mut environment: MarkerEnvironment = MarkerEnvironment::new();
+    for source in environment_sources.iter() {
+        let result = if source.trim_start().starts_with('{') {
+            MarkerEnvironment::from_json(source).map(|json| environment.values.extend(json.values))
+        } else if source.contains('=') {
+            environment.insert_pair(source)
+        } else {
+            let content: String = match fs::read_to_string(source) {
+                Ok(content) => content,
+                Err(error) => exit_on_error(&format!("Cannot read '{path}': {error}", path=source, error=error)),
+            };
+            MarkerEnvironment::from_json(&content).map(|json| environment.values.extend(json.values))
+        };
+        if let Err(error) = result {
+            exit_on_error(&format!("{error} in '{source}'", error=error, source=source));
+        }
+    }
+    match marker.evaluate(&environment) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(error) => exit_on_error(&error.to_string()),
+    }
 }
 
-fn assert_operation(version_a: &str, version_b: &str, operator: &str, parser_config: compare::ParserConfig) -> i32 {
-    let order = compare::compare_with_operator(
-        version_a, version_b, operator, &parser_config);
-    match order {
+/// Sorts the versions read from `parameters` files, or standard input, one per line.
+///
+/// Lines that do not parse are reported on standard error and left out.
+fn sort_operation(parameters: &[String], parser_config: ParserConfig) -> i32 {
+    let reverse: bool = parameters.iter().any(|p| p == "--reverse");
+    let unique: bool = parameters.iter().any(|p| p == "--unique");
+    let paths: Vec<&String> = parameters.iter().filter(|p| *p != "--reverse" && *p != "--unique").collect();
+
+    let mut sources: Vec<(String, String)> = Vec::new();
+    if paths.is_empty() {
+        let mut content: String = String::new();
+        if let Err(error) = io::stdin().read_to_string(&mut content) {
+            exit_on_error(&format!("Cannot read standard input: {error}", error=error));
+        }
+        sources.push(("stdin".to_string(), content));
+    }
+    for path in paths {
+        match fs::read_to_string(path) {
+            Ok(content) => sources.push((path.to_string(), content)),
+            Err(error) => exit_on_error(&format!("Cannot read '{path}': {error}", path=path, error=error)),
+        }
+    }
+
+    let sources: Vec<(&str, &str)> = sources.iter().map(|(source, content)| (source.as_str(), content.as_str())).collect();
+    let (lines, errors): (Vec<String>, Vec<String>) = lists::sort_lines(&sources, reverse, unique, &parser_config);
+    for error in errors.iter() {
+        eprintln!("{}", error);
+    }
+    for line in lines.iter() {
+        println!("{}", line);
+    }
+    match errors.is_empty() {
         true => 0,
         false => 1,
     }
 }
+
+/// Prints the greatest (`wanted` is `Greater`) or least version among `parameters`,
+/// or the lines of standard input when no version is given.
+///
+/// Strings that do not parse are reported on standard error and left out.
+fn extremum_operation(parameters: &[String], wanted: Ordering, parser_config: ParserConfig) -> i32 {
+    let mut requirement: Option<VersionReq> = None;
+    let mut exclude_prerelease: bool = false;
+    let mut candidates: Vec<String> = Vec::new();
+    let mut parameters = parameters.iter();
+    while let Some(parameter) = parameters.next() {
+        match parameter.as_str() {
+            "--exclude-prerelease" => exclude_prerelease = true,
+            "--satisfying" => {
+                let raw_requirement: &String = match parameters.next() {
+                    Some(raw_requirement) => raw_requirement,
+                    None => exit_on_error("Missing requirement after '--satisfying'."),
+                };
+                requirement = match VersionReq::parse(raw_requirement, &parser_config) {
+                    Ok(requirement) => Some(requirement),
+                    Err(error) => exit_on_error(&error.to_string()),
+                };
+            },
+            _ => candidates.push(parameter.to_string()),
+        }
+    }
+    if candidates.is_empty() {
+        let mut content: String = String::new();
+        if let Err(error) = io::stdin().read_to_string(&mut content) {
+            exit_on_error(&format!("Cannot read standard input: {error}", error=error));
+        }
+        candidates = content.lines().map(|line| line.trim().to_string()).filter(|line| !line.is_empty()).collect();
+    }
+
+    let candidates: Vec<&str> = candidates.iter().map(String::as_str).collect();
+    let (best, errors): (Option<String>, Vec<String>) = lists::extremum(&candidates, wanted, requirement.as_ref(), exclude_prerelease, &parser_config);
+    for error in errors.iter() {
+        eprintln!("{}", error);
+    }
+    match best {
+        Some(candidate) => {
+            println!("{}", candidate);
+            0
+        },
+        None => 1,
+    }
+}
+
+fn bump_operation(raw_part: &str, raw_version: &str, parser_config: ParserConfig) {
+    let part: Part = match Part::from_name(raw_part) {
+        Some(part) => part,
+        None => exit_on_error(&format!("Invalid part '{part}'. Use 'major', 'minor', 'patch', 'pre', 'build' or 'epoch'.", part=raw_part)),
+    };
+    let version: Version = match Version::parse_with(raw_version, &parser_config) {
+        Ok(version) => version,
+        Err(error) => exit_on_error(&error.to_string()),
+    };
+    match version.bump(part) {
+        Some(bumped) => println!("{}", bumped.to_string_with(&parser_config)),
+        None => exit_on_error(&format!("'{version}' has no pre-release number to bump.", version=raw_version)),
+    }
+}
+
+fn parse_operation(parameters: &[String], parser_config: ParserConfig) {
+    let mut format: &str = "table";
+    let mut raw_versions: Vec<&String> = Vec::new();
+    let mut parameters = parameters.iter();
+    while let Some(parameter) = parameters.next() {
+        match parameter.as_str() {
+            "--format" => match parameters.next() {
+                Some(value) => format = value,
+                None => exit_on_error("Missing format after '--format'."),
+            },
+            _ => raw_versions.push(parameter),
+        }
+    }
+    let raw_version: &String = match raw_versions.as_slice() {
+        [raw_version] => raw_version,
+        [] => exit_on_error("Missing parameters"),
+        _ => exit_on_error("The 'parse' verb takes a single version."),
+    };
+    let version: Version = match Version::parse_with(raw_version, &parser_config) {
+        Ok(version) => version,
+        Err(error) => exit_on_error(&error.to_string()),
+    };
+    match format {
+        "table" => println!("{}", display::table(&version)),
+        "json" => println!("{}", display::json(&version)),
+        _ => exit_on_error(&format!("Invalid format '{format}'. Use 'table' or 'json'.", format=format)),
+    }
+}
@@ -0,0 +1,372 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::super::errors::ParseError;
+use super::super::schemes::{parse_pre_release_identifier, split_identifiers, Identifier, SemVer};
+
+/// Operator of a [`CargoComparator`]. A bare version is [`CargoOperator::Caret`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CargoOperator {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+    /// `1.*` or `1.2.*`
+    Wildcard,
+}
+
+/// One constraint of a [`CargoReq`]. Missing numbers are `None`: `~1` has no minor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CargoComparator {
+    pub operator: CargoOperator,
+    pub major: u64,
+    pub minor: Option<u64>,
+    pub patch: Option<u64>,
+    pub pre_release: Vec<Identifier>,
+}
+
+/// A `Cargo.toml` version requirement, such as `1.2`, `^0.3`, `~1.2.3`, `=1.0.0`,
+/// `>=1.2, <1.5` or `*`, matched like Cargo's `semver` crate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CargoReq {
+    pub comparators: Vec<CargoComparator>,
+}
+
+impl CargoReq {
+    pub fn parse(raw_requirement: &str) -> Result<CargoReq, ParseError> {
+        if raw_requirement.trim() == "*" {
+            return Ok(CargoReq { comparators: Vec::new() })
+        }
+        let mut comparators: Vec<CargoComparator> = Vec::new();
+        let mut offset: usize = 0;
+        for raw_comparator in raw_requirement.split(',') {
+            comparators.push(parse_comparator(raw_comparator, offset)?);
+            offset += raw_comparator.len() + 1;
+        }
+        Ok(CargoReq { comparators })
+    }
+
+    /// Whether `version` matches every comparator.
+    ///
+    /// As in Cargo, a pre-release only matches if a comparator has a
+    /// pre-release with the same `major.minor.patch`.
+    pub fn matches(&self, version: &SemVer) -> bool {
+        if !self.comparators.iter().all(|comparator| comparator.matches(version)) {
+            return false
+        }
+        if !version.is_prerelease() {
+            return true
+        }
+        self.comparators.iter().any(|comparator| {
+            comparator.major == version.major
+                && comparator.minor == Some(version.minor)
+                && comparator.patch == Some(version.patch)
+                && !comparator.pre_release.is_empty()
+        })
+    }
+}
+
+impl CargoComparator {
+    pub fn matches(&self, version: &SemVer) -> bool {
+        match self.operator {
+            CargoOperator::Exact | CargoOperator::Wildcard => self.matches_exact(version),
+            CargoOperator::Greater => self.matches_greater(version),
+            CargoOperator::GreaterEq => self.matches_exact(version) || self.matches_greater(version),
+            CargoOperator::Less => self.matches_less(version),
+            CargoOperator::LessEq => self.matches_exact(version) || self.matches_less(version),
+            CargoOperator::Tilde => self.matches_tilde(version),
+            CargoOperator::Caret => self.matches_caret(version),
+        }
+    }
+
+    fn cmp_pre_release(&self, version: &SemVer) -> Ordering {
+        match [version.pre_release.is_empty(), self.pre_release.is_empty()] {
+            [true, true] => Ordering::Equal,
+            [true, false] => Ordering::Greater,
+            [false, true] => Ordering::Less,
+            [false, false] => version.pre_release.cmp(&self.pre_release),
+        }
+    }
+
+    /// Orders `version` relative to the comparator, number by number,
+    /// `None` when a missing number is reached first.
+    fn cmp_numbers(&self, version: &SemVer) -> Option<Ordering> {
+        if version.major != self.major {
+            return Some(version.major.cmp(&self.major))
+        }
+        let minor: u64 = self.minor?;
+        if version.minor != minor {
+            return Some(version.minor.cmp(&minor))
+        }
+        let patch: u64 = self.patch?;
+        if version.patch != patch {
+            return Some(version.patch.cmp(&patch))
+        }
+        Some(self.cmp_pre_release(version))
+    }
+
+    fn matches_exact(&self, version: &SemVer) -> bool {
+        version.major == self.major
+            && self.minor.is_none_or(|minor| version.minor == minor)
+            && self.patch.is_none_or(|patch| version.patch == patch)
+            && version.pre_release == self.pre_release
+    }
+
+    fn matches_greater(&self, version: &SemVer) -> bool {
+        self.cmp_numbers(version) == Some(Ordering::Greater)
+    }
+
+    fn matches_less(&self, version: &SemVer) -> bool {
+        self.cmp_numbers(version) == Some(Ordering::Less)
+    }
+
+    fn matches_tilde(&self, version: &SemVer) -> bool {
+        if version.major != self.major || self.minor.is_some_and(|minor| version.minor != minor) {
+            return false
+        }
+        if let Some(patch) = self.patch {
+            if version.patch != patch {
+                return version.patch > patch
+            }
+        }
+        self.cmp_pre_release(version) != Ordering::Less
+    }
+
+    /// Allows changes that keep the first non-zero number:
+    /// `^1.2.3` is `>=1.2.3, <2.0.0`, `^0.2.3` is `>=0.2.3, <0.3.0`, `^0.0.3` is `=0.0.3`.
+    fn matches_caret(&self, version: &SemVer) -> bool {
+        if version.major != self.major {
+            return false
+        }
+        let minor: u64 = match self.minor {
+            Some(minor) => minor,
+            None => return true,
+        };
+        let patch: u64 = match self.patch {
+            Some(patch) => patch,
+            None if self.major > 0 => return version.minor >= minor,
+            None => return version.minor == minor,
+        };
+        if self.major > 0 {
+            if version.minor != minor {
+                return version.minor > minor
+            } else if version.patch != patch {
+                return version.patch > patch
+            }
+        } else if minor > 0 {
+            if version.minor != minor {
+                return false
+            } else if version.patch != patch {
+                return version.patch > patch
+            }
+        } else if version.minor != minor || version.patch != patch {
+            return false
+        }
+        self.cmp_pre_release(version) != Ordering::Less
+    }
+}
+
+fn parse_comparator(raw_comparator: &str, offset: usize) -> Result<CargoComparator, ParseError> {
+    let operator_start: usize = raw_comparator.len() - raw_comparator.trim_start().len();
+    let trimmed: &str = raw_comparator.trim();
+    if trimmed.is_empty() {
+        return Err(ParseError::EmptyComponent { offset: offset + operator_start })
+    }
+    let operator_len: usize = trimmed.find(|c| !"<>=~^".contains(c)).unwrap_or(trimmed.len());
+    let operator: CargoOperator = match &trimmed[..operator_len] {
+        "" => CargoOperator::Caret,
+        "=" => CargoOperator::Exact,
+        ">" => CargoOperator::Greater,
+        ">=" => CargoOperator::GreaterEq,
+        "<" => CargoOperator::Less,
+        "<=" => CargoOperator::LessEq,
+        "~" => CargoOperator::Tilde,
+        "^" => CargoOperator::Caret,
+        raw_operator => return Err(ParseError::InvalidOperator { operator: raw_operator.to_string(), offset: offset + operator_start }),
+    };
+    let raw_version: &str = trimmed[operator_len..].trim_start();
+    let version_start: usize = offset + operator_start + trimmed.len() - raw_version.len();
+    let (raw_main, raw_pre_release): (&str, Option<&str>) = match raw_version.find('-') {
+        Some(index) => (&raw_version[..index], Some(&raw_version[index + 1..])),
+        None => (raw_version, None),
+    };
+
+    let mut numbers: Vec<Option<u64>> = Vec::new();
+    let mut number_offset: usize = version_start;
+    for raw_number in raw_main.split('.') {
+        if numbers.len() == 3 {
+            return Err(ParseError::UnexpectedCharacter { character: '.', offset: number_offset - 1 })
+        }
+        numbers.push(parse_number(raw_number, number_offset, numbers.is_empty())?);
+        number_offset += raw_number.len() + 1;
+    }
+    let minor: Option<u64> = numbers.get(1).cloned().flatten();
+    let patch: Option<u64> = numbers.get(2).cloned().flatten();
+    let wildcard: bool = numbers.contains(&None);
+    if minor.is_none() && patch.is_some() {
+        // `1.*.3`
+        let index: usize = raw_main.find(['*', 'x', 'X']).unwrap_or(0);
+        let character: char = raw_main[index..].chars().next().unwrap_or('*');
+        return Err(ParseError::UnexpectedCharacter { character, offset: version_start + index })
+    }
+
+    let pre_release: Vec<Identifier> = match raw_pre_release {
+        Some(_) if patch.is_none() => {
+            return Err(ParseError::UnexpectedCharacter { character: '-', offset: version_start + raw_main.len() })
+        },
+        Some(s) => split_identifiers(s, version_start + raw_main.len() + 1)?
+            .into_iter()
+            .map(|(identifier, identifier_offset)| parse_pre_release_identifier(identifier, identifier_offset))
+            .collect::<Result<Vec<Identifier>, ParseError>>()?,
+        None => Vec::new(),
+    };
+    let operator: CargoOperator = match (wildcard, operator) {
+        (true, CargoOperator::Exact) | (true, CargoOperator::Caret) => CargoOperator::Wildcard,
+        _ => operator,
+    };
+    Ok(CargoComparator {
+        operator,
+        major: numbers[0].unwrap_or(0),
+        minor,
+        patch,
+        pre_release,
+    })
+}
+
+/// Parses a number of a requirement, or `*`, `x`, `X` after the major.
+fn parse_number(s: &str, offset: usize, is_major: bool) -> Result<Option<u64>, ParseError> {
+    if s.is_empty() {
+        return Err(ParseError::EmptyComponent { offset })
+    }
+    if !is_major && (s == "*" || s == "x" || s == "X") {
+        return Ok(None)
+    }
+    if let Some((index, character)) = s.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        return Err(ParseError::UnexpectedCharacter { character, offset: offset + index })
+    }
+    if s.len() > 1 && s.starts_with('0') {
+        return Err(ParseError::LeadingZero { offset })
+    }
+    s.parse().map(Some).map_err(|_| ParseError::NumericOverflow { offset })
+}
+
+impl fmt::Display for CargoReq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.comparators.is_empty() {
+            return write!(f, "*")
+        }
+        let comparators: Vec<String> = self.comparators.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}", comparators.join(", "))
+    }
+}
+
+impl fmt::Display for CargoComparator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operator: &str = match self.operator {
+            CargoOperator::Exact => "=",
+            CargoOperator::Greater => ">",
+            CargoOperator::GreaterEq => ">=",
+            CargoOperator::Less => "<",
+            CargoOperator::LessEq => "<=",
+            CargoOperator::Tilde => "~",
+            CargoOperator::Caret => "^",
+            CargoOperator::Wildcard => "",
+        };
+        write!(f, "{}{}", operator, self.major)?;
+        match (self.minor, self.patch) {
+            (Some(minor), Some(patch)) => write!(f, ".{}.{}", minor, patch)?,
+            (Some(minor), None) => write!(f, ".{}", minor)?,
+            (None, _) => {},
+        }
+        if self.operator == CargoOperator::Wildcard {
+            write!(f, ".*")?;
+        }
+        if !self.pre_release.is_empty() {
+            let identifiers: Vec<String> = self.pre_release.iter().map(|i| i.to_string()).collect();
+            write!(f, "-{}", identifiers.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn satisfies(raw_version: &str, raw_requirement: &str) -> bool {
+        CargoReq::parse(raw_requirement).unwrap().matches(&SemVer::parse(raw_version).unwrap())
+    }
+
+    fn assert_matches(raw_requirement: &str, matching: &[&str], not_matching: &[&str]) {
+        for raw_version in matching.iter() {
+            assert!(satisfies(raw_version, raw_requirement), "{} matches {}", raw_version, raw_requirement);
+        }
+        for raw_version in not_matching.iter() {
+            assert!(!satisfies(raw_version, raw_requirement), "{} does not match {}", raw_version, raw_requirement);
+        }
+    }
+
+    // Cases from the Cargo book and the semver crate.
+    #[test]
+    fn test_default_is_caret() {
+        assert_matches("1.2.3", &["1.2.3", "1.9.0"], &["1.2.2", "2.0.0"]);
+        assert_matches("1.2", &["1.2.0", "1.99.0"], &["1.1.9", "2.0.0"]);
+        assert_matches("1", &["1.0.0", "1.99.0"], &["0.9.0", "2.0.0"]);
+        assert_matches("0.2.3", &["0.2.3", "0.2.99"], &["0.2.2", "0.3.0"]);
+        assert_matches("0.2", &["0.2.0", "0.2.99"], &["0.3.0"]);
+        assert_matches("0.0.3", &["0.0.3"], &["0.0.4", "0.0.2"]);
+        assert_matches("0.0", &["0.0.0", "0.0.99"], &["0.1.0"]);
+        assert_matches("0", &["0.0.0", "0.99.0"], &["1.0.0"]);
+        assert_eq!(CargoReq::parse("1.2").unwrap(), CargoReq::parse("^1.2").unwrap());
+    }
+
+    #[test]
+    fn test_tilde() {
+        assert_matches("~1.2.3", &["1.2.3", "1.2.99"], &["1.3.0", "1.2.2"]);
+        assert_matches("~1.2", &["1.2.0", "1.2.99"], &["1.3.0"]);
+        assert_matches("~1", &["1.0.0", "1.99.0"], &["2.0.0"]);
+    }
+
+    #[test]
+    fn test_wildcard() {
+        assert_matches("*", &["0.0.1", "99.0.0"], &["1.0.0-alpha"]);
+        assert_matches("1.*", &["1.0.0", "1.99.0"], &["2.0.0", "0.9.0"]);
+        assert_matches("1.2.*", &["1.2.0", "1.2.99"], &["1.3.0"]);
+    }
+
+    #[test]
+    fn test_comparison_and_exact() {
+        assert_matches(">= 1.2.0, < 1.5", &["1.2.0", "1.4.99"], &["1.5.0", "1.1.0"]);
+        assert_matches("=1.2.3", &["1.2.3"], &["1.2.4"]);
+        assert_matches("<=1.2", &["1.2.99", "0.1.0"], &["1.3.0"]);
+        assert_matches(">1", &["2.0.0"], &["1.99.0"]);
+    }
+
+    #[test]
+    fn test_prerelease_needs_same_numbers() {
+        assert_matches("^1.2.3-alpha.1", &["1.2.3-alpha.2", "1.2.3", "1.5.0"], &["1.2.3-alpha.0", "1.5.0-beta"]);
+        assert_matches(">=1.0.0", &["1.1.0"], &["1.1.0-rc.1"]);
+        assert_matches("=1.0.0-rc.1", &["1.0.0-rc.1"], &["1.0.0"]);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(CargoReq::parse(">= 1.2.0, <1.5").unwrap().to_string(), ">=1.2.0, <1.5");
+        assert_eq!(CargoReq::parse("1.2.x").unwrap().to_string(), "1.2.*");
+        assert_eq!(CargoReq::parse("1").unwrap().to_string(), "^1");
+    }
+
+    #[test]
+    fn test_invalid_requirements() {
+        assert_eq!(CargoReq::parse("=>1.0"), Err(ParseError::InvalidOperator { operator: "=>".to_string(), offset: 0 }));
+        assert_eq!(CargoReq::parse("1.0, 01.0"), Err(ParseError::LeadingZero { offset: 5 }));
+        assert_eq!(CargoReq::parse("1.2-beta"), Err(ParseError::UnexpectedCharacter { character: '-', offset: 3 }));
+        assert_eq!(CargoReq::parse("1.*.3"), Err(ParseError::UnexpectedCharacter { character: '*', offset: 2 }));
+        assert_eq!(CargoReq::parse("1.0,"), Err(ParseError::EmptyComponent { offset: 4 }));
+        assert_eq!(CargoReq::parse("v1.0"), Err(ParseError::UnexpectedCharacter { character: 'v', offset: 0 }));
+    }
+}
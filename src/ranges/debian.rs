@@ -0,0 +1,389 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+use super::super::errors::ParseError;
+use super::super::schemes::DebianVersion;
+
+/// Operator of a versioned [`Dependency`], as in Debian policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelationOperator {
+    /// `<<`
+    StrictlyEarlier,
+    /// `<=`, or the obsolete `<`
+    EarlierOrEqual,
+    /// `=`
+    ExactlyEqual,
+    /// `>=`, or the obsolete `>`
+    LaterOrEqual,
+    /// `>>`
+    StrictlyLater,
+}
+
+/// One package of a [`Relation`], like `libssl3 (>= 3.0.0)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dependency {
+    pub package: String,
+    /// Architecture qualifier, like `any` in `python3:any`.
+    pub architecture: Option<String>,
+    pub constraint: Option<(RelationOperator, DebianVersion)>,
+}
+
+/// Alternatives separated by `|`, satisfied by any of them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Relation {
+    pub alternatives: Vec<Dependency>,
+}
+
+/// A `Depends:`-style field: comma-separated relations, all of them required.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelationField {
+    pub relations: Vec<Relation>,
+}
+
+/// What is installed, by package name.
+///
+/// Each name maps to the versions that can satisfy a dependency on it: the
+/// version of a real package, or the version of a `Provides:`, `None` when
+/// unversioned.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstalledPackages {
+    pub packages: HashMap<String, Vec<Option<DebianVersion>>>,
+}
+
+impl RelationField {
+    pub fn parse(raw_field: &str) -> Result<RelationField, ParseError> {
+        let mut relations: Vec<Relation> = Vec::new();
+        let mut offset: usize = 0;
+        for raw_relation in raw_field.split(',') {
+            relations.push(Relation::parse(raw_relation).map_err(|e| e.shifted(offset))?);
+            offset += raw_relation.len() + 1;
+        }
+        Ok(RelationField { relations })
+    }
+
+    /// The relations that `installed` does not satisfy.
+    pub fn unsatisfied(&self, installed: &InstalledPackages) -> Vec<&Relation> {
+        self.relations.iter().filter(|relation| !relation.is_satisfied_by(installed)).collect()
+    }
+}
+
+impl Relation {
+    pub fn parse(raw_relation: &str) -> Result<Relation, ParseError> {
+        let mut alternatives: Vec<Dependency> = Vec::new();
+        let mut offset: usize = 0;
+        for raw_dependency in raw_relation.split('|') {
+            alternatives.push(Dependency::parse(raw_dependency).map_err(|e| e.shifted(offset))?);
+            offset += raw_dependency.len() + 1;
+        }
+        Ok(Relation { alternatives })
+    }
+
+    pub fn is_satisfied_by(&self, installed: &InstalledPackages) -> bool {
+        self.alternatives.iter().any(|dependency| dependency.is_satisfied_by(installed))
+    }
+}
+
+impl Dependency {
+    pub fn parse(raw_dependency: &str) -> Result<Dependency, ParseError> {
+        let start: usize = raw_dependency.len() - raw_dependency.trim_start().len();
+        let trimmed: &str = raw_dependency.trim();
+        let name_len: usize = trimmed.find(|c: char| c.is_whitespace() || c == '(').unwrap_or(trimmed.len());
+        let (package, architecture): (&str, Option<&str>) = match trimmed[..name_len].split_once(':') {
+            Some((package, architecture)) => (package, Some(architecture)),
+            None => (&trimmed[..name_len], None),
+        };
+        check_name(package, start)?;
+        if let Some(architecture) = architecture {
+            check_name(architecture, start + package.len() + 1)?;
+        }
+
+        let rest: &str = trimmed[name_len..].trim_start();
+        let rest_offset: usize = start + trimmed.len() - rest.len();
+        let constraint: Option<(RelationOperator, DebianVersion)> = match rest.chars().next() {
+            None => None,
+            Some('(') => Some(parse_constraint(rest, rest_offset)?),
+            Some(character) => return Err(ParseError::UnexpectedCharacter { character, offset: rest_offset }),
+        };
+        Ok(Dependency {
+            package: package.to_string(),
+            architecture: architecture.map(|s| s.to_string()),
+            constraint,
+        })
+    }
+
+    pub fn is_satisfied_by(&self, installed: &InstalledPackages) -> bool {
+        let candidates: &[Option<DebianVersion>] = match installed.packages.get(&self.package) {
+            Some(candidates) => candidates,
+            None => return false,
+        };
+        match &self.constraint {
+            None => !candidates.is_empty(),
+            Some((operator, version)) => candidates.iter()
+                .flatten()
+                .any(|candidate| operator.matches(candidate.cmp(version))),
+        }
+    }
+}
+
+/// Package names are lowercase letters, digits, `+`, `-` and `.`.
+fn check_name(name: &str, offset: usize) -> Result<(), ParseError> {
+    if name.is_empty() {
+        return Err(ParseError::EmptyComponent { offset })
+    }
+    match name.char_indices().find(|(_, c)| !(c.is_ascii_lowercase() || c.is_ascii_digit() || "+-.".contains(*c))) {
+        Some((index, character)) => Err(ParseError::UnexpectedCharacter { character, offset: offset + index }),
+        None => Ok(()),
+    }
+}
+
+/// Parses `(operator version)`.
+fn parse_constraint(s: &str, offset: usize) -> Result<(RelationOperator, DebianVersion), ParseError> {
+    let close: usize = match s.find(')') {
+        Some(index) => index,
+        None => return Err(ParseError::EmptyComponent { offset: offset + s.len() }),
+    };
+    if let Some(character) = s[close + 1..].chars().next() {
+        return Err(ParseError::UnexpectedCharacter { character, offset: offset + close + 1 })
+    }
+    let inner: &str = s[1..close].trim_start();
+    let inner_offset: usize = offset + close - inner.len();
+    let operator_len: usize = inner.find(|c| !"<>=".contains(c)).unwrap_or(inner.len());
+    let operator: RelationOperator = match &inner[..operator_len] {
+        "<<" => RelationOperator::StrictlyEarlier,
+        // `<` and `>` are obsolete spellings of `<=` and `>=`, still accepted by dpkg.
+        "<=" | "<" => RelationOperator::EarlierOrEqual,
+        "=" => RelationOperator::ExactlyEqual,
+        ">=" | ">" => RelationOperator::LaterOrEqual,
+        ">>" => RelationOperator::StrictlyLater,
+        raw_operator => return Err(ParseError::InvalidOperator { operator: raw_operator.to_string(), offset: inner_offset }),
+    };
+    let raw_version: &str = inner[operator_len..].trim();
+    let version_offset: usize = inner_offset + inner.len() - inner[operator_len..].trim_start().len();
+    let version: DebianVersion = DebianVersion::parse(raw_version).map_err(|e| e.shifted(version_offset))?;
+    Ok((operator, version))
+}
+
+impl RelationOperator {
+    /// Tells if `order`, the result of comparing the installed version to the
+    /// constraint one, satisfies this operator.
+    pub fn matches(self, order: Ordering) -> bool {
+        match self {
+            RelationOperator::StrictlyEarlier => order == Ordering::Less,
+            RelationOperator::EarlierOrEqual => order != Ordering::Greater,
+            RelationOperator::ExactlyEqual => order == Ordering::Equal,
+            RelationOperator::LaterOrEqual => order != Ordering::Less,
+            RelationOperator::StrictlyLater => order == Ordering::Greater,
+        }
+    }
+}
+
+impl InstalledPackages {
+    pub fn new() -> InstalledPackages {
+        InstalledPackages::default()
+    }
+
+    pub fn insert(&mut self, package: &str, version: Option<DebianVersion>) {
+        self.packages.entry(package.to_string()).or_default().push(version);
+    }
+
+    /// Reads a `/var/lib/dpkg/status`-format file.
+    ///
+    /// Only stanzas whose `Status:` ends with `installed` count. Their
+    /// `Provides:` are recorded as well.
+    pub fn parse_dpkg_status(content: &str) -> Result<InstalledPackages, ParseError> {
+        let mut installed: InstalledPackages = InstalledPackages::new();
+        let mut stanza: HashMap<String, (String, usize)> = HashMap::new();
+        let mut current_field: Option<String> = None;
+        let mut offset: usize = 0;
+        for line in content.split('\n').chain(std::iter::once("")) {
+            let line_offset: usize = offset;
+            offset += line.len() + 1;
+            let line: &str = line.trim_end_matches('\r');
+            if line.trim().is_empty() {
+                installed.add_stanza(&stanza)?;
+                stanza.clear();
+                current_field = None;
+            } else if line.starts_with([' ', '\t']) {
+                // Continuation of a multi-line field
+                if let Some((value, _)) = current_field.as_ref().and_then(|field| stanza.get_mut(field)) {
+                    value.push('\n');
+                    value.push_str(line);
+                }
+            } else {
+                let (field, value): (&str, &str) = match line.split_once(':') {
+                    Some(found) => found,
+                    None => return Err(ParseError::EmptyComponent { offset: line_offset + line.len() }),
+                };
+                let value_offset: usize = line_offset + line.len() - value.len();
+                let field: String = field.to_ascii_lowercase();
+                stanza.insert(field.clone(), (value.to_string(), value_offset));
+                current_field = Some(field);
+            }
+        }
+        Ok(installed)
+    }
+
+    fn add_stanza(&mut self, stanza: &HashMap<String, (String, usize)>) -> Result<(), ParseError> {
+        let is_installed: bool = stanza.get("status")
+            .is_some_and(|(status, _)| status.split_whitespace().last() == Some("installed"));
+        let package: &str = match stanza.get("package") {
+            Some((package, _)) if is_installed => package.trim(),
+            _ => return Ok(()),
+        };
+        let version: Option<DebianVersion> = match stanza.get("version") {
+            Some((raw_version, offset)) => {
+                let start: usize = raw_version.len() - raw_version.trim_start().len();
+                Some(DebianVersion::parse(raw_version.trim()).map_err(|e| e.shifted(offset + start))?)
+            },
+            None => None,
+        };
+        self.insert(package, version);
+        if let Some((raw_provides, offset)) = stanza.get("provides") {
+            let provides: RelationField = RelationField::parse(raw_provides).map_err(|e| e.shifted(*offset))?;
+            for dependency in provides.relations.iter().flat_map(|relation| relation.alternatives.iter()) {
+                let version: Option<DebianVersion> = match &dependency.constraint {
+                    Some((RelationOperator::ExactlyEqual, version)) => Some(version.clone()),
+                    _ => None,
+                };
+                self.insert(&dependency.package, version);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for RelationField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let relations: Vec<String> = self.relations.iter().map(|r| r.to_string()).collect();
+        write!(f, "{}", relations.join(", "))
+    }
+}
+
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let alternatives: Vec<String> = self.alternatives.iter().map(|d| d.to_string()).collect();
+        write!(f, "{}", alternatives.join(" | "))
+    }
+}
+
+impl fmt::Display for Dependency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.package)?;
+        if let Some(architecture) = &self.architecture {
+            write!(f, ":{}", architecture)?;
+        }
+        if let Some((operator, version)) = &self.constraint {
+            write!(f, " ({} {})", operator, version)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for RelationOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            RelationOperator::StrictlyEarlier => "<<",
+            RelationOperator::EarlierOrEqual => "<=",
+            RelationOperator::ExactlyEqual => "=",
+            RelationOperator::LaterOrEqual => ">=",
+            RelationOperator::StrictlyLater => ">>",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATUS: &str = "Package: libc6
+Status: install ok installed
+Architecture: amd64
+Version: 2.36-9+deb12u4
+Description: GNU C Library: Shared libraries
+ Contains the standard libraries that are used by nearly all programs on
+ the system.
+
+Package: libssl1.1
+Status: deinstall ok config-files
+Version: 1.1.1n-0+deb11u5
+
+Package: libssl3
+Status: install ok installed
+Version: 3.0.11-1~deb12u2
+
+Package: postfix
+Status: install ok installed
+Version: 3.7.10-0+deb12u1
+Provides: default-mta, mail-transport-agent
+";
+
+    fn installed() -> InstalledPackages {
+        InstalledPackages::parse_dpkg_status(STATUS).unwrap()
+    }
+
+    fn satisfied(raw_relation: &str) -> bool {
+        Relation::parse(raw_relation).unwrap().is_satisfied_by(&installed())
+    }
+
+    #[test]
+    fn test_parse_field() {
+        let field: RelationField = RelationField::parse("libc6 (>= 2.31), libssl3 (>= 3.0.0) | libssl1.1(>=1.1.1), python3:any").unwrap();
+        assert_eq!(field.relations.len(), 3);
+        assert_eq!(field.relations[1].alternatives[1].package, "libssl1.1");
+        assert_eq!(field.relations[2].alternatives[0].architecture, Some("any".to_string()));
+        assert_eq!(field.to_string(), "libc6 (>= 2.31), libssl3 (>= 3.0.0) | libssl1.1 (>= 1.1.1), python3:any");
+    }
+
+    #[test]
+    fn test_operators() {
+        assert!(satisfied("libc6 (>= 2.31)"));
+        assert!(satisfied("libc6 (>> 2.36-9)"));
+        assert!(satisfied("libc6 (= 2.36-9+deb12u4)"));
+        assert!(satisfied("libc6 (<= 2.36-9+deb12u4)"));
+        assert!(satisfied("libc6 (<< 2.37)"));
+        assert!(!satisfied("libc6 (<< 2.36)"));
+        assert!(!satisfied("libssl3 (>= 3.0.11-1)"));
+        assert!(satisfied("libssl3 (>= 3.0.11-1~)"));
+    }
+
+    #[test]
+    fn test_obsolete_operators() {
+        let field: RelationField = RelationField::parse("libc6 (< 2.37), libssl3 (> 3.0.0)").unwrap();
+        assert_eq!(field.relations[0].alternatives[0].constraint.as_ref().map(|(operator, _)| *operator), Some(RelationOperator::EarlierOrEqual));
+        assert_eq!(field.to_string(), "libc6 (<= 2.37), libssl3 (>= 3.0.0)");
+    }
+
+    #[test]
+    fn test_alternatives_and_removed_packages() {
+        assert!(!satisfied("libssl1.1"));
+        assert!(satisfied("libssl1.1 (>= 1.1.1) | libssl3 (>= 3.0.0)"));
+        assert!(!satisfied("libssl1.1 (>= 1.1.1) | libssl3 (>= 4)"));
+    }
+
+    #[test]
+    fn test_provides() {
+        assert!(satisfied("mail-transport-agent"));
+        assert!(!satisfied("mail-transport-agent (>= 1)"));
+    }
+
+    #[test]
+    fn test_unsatisfied() {
+        let field: RelationField = RelationField::parse("libc6 (>= 2.31), libssl1.1, libssl3 (>= 4) | postfix").unwrap();
+        let unsatisfied: Vec<String> = field.unsatisfied(&installed()).iter().map(|r| r.to_string()).collect();
+        assert_eq!(unsatisfied, vec!["libssl1.1"]);
+    }
+
+    #[test]
+    fn test_invalid_relations() {
+        assert_eq!(RelationField::parse("libc6 (=> 2.31)"), Err(ParseError::InvalidOperator { operator: "=>".to_string(), offset: 7 }));
+        assert_eq!(RelationField::parse("libc6, Libssl3"), Err(ParseError::UnexpectedCharacter { character: 'L', offset: 7 }));
+        assert_eq!(RelationField::parse("libc6 (>= 2.31"), Err(ParseError::EmptyComponent { offset: 14 }));
+        assert_eq!(RelationField::parse("libc6 (>= x)"), Err(ParseError::UnexpectedCharacter { character: 'x', offset: 10 }));
+        assert_eq!(RelationField::parse("libc6,, libssl3"), Err(ParseError::EmptyComponent { offset: 6 }));
+        assert_eq!(RelationField::parse("libc6 2.31"), Err(ParseError::UnexpectedCharacter { character: '2', offset: 6 }));
+        assert_eq!(InstalledPackages::parse_dpkg_status("Package: a\nStatus: install ok installed\nVersion: x\n"), Err(ParseError::UnexpectedCharacter { character: 'x', offset: 49 }));
+    }
+}
@@ -0,0 +1,248 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::super::errors::ParseError;
+
+/// One end of an [`Interval`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bound {
+    pub version: String,
+    pub inclusive: bool,
+}
+
+/// `[1.0,2.0)`, `(,1.0]`, `[1.5,)` or `[1.2]`. A missing bound is unlimited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interval {
+    pub lower: Option<Bound>,
+    pub upper: Option<Bound>,
+}
+
+/// A Maven or NuGet version range: a comma-separated union of intervals,
+/// like `[1.0,1.2),(1.5,)`.
+///
+/// Versions are kept as written and ordered by a comparison function, so a
+/// range can be used with any scheme:
+///
+/// ```
+/// use cvers::{IntervalRange, MavenScheme, VersionScheme};
+///
+/// let range = IntervalRange::parse("[1.0,2.0)", |a, b| MavenScheme.compare(a, b)).unwrap();
+/// assert_eq!(range.contains("2.0-rc1", |a, b| MavenScheme.compare(a, b)), Ok(true));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalRange {
+    pub intervals: Vec<Interval>,
+}
+
+/// Whether `raw_range` is written in interval notation.
+pub fn is_interval_range(raw_range: &str) -> bool {
+    raw_range.trim_start().starts_with(['[', '('])
+}
+
+impl IntervalRange {
+    /// Parses `raw_range`, checking each version and the order of the bounds with `compare`.
+    ///
+    /// A bare version, which Maven reads as a soft requirement and NuGet as a
+    /// minimum, is rejected: write `[1.0,)` for the NuGet meaning.
+    pub fn parse<F>(raw_range: &str, compare: F) -> Result<IntervalRange, ParseError>
+    where F: Fn(&str, &str) -> Result<Ordering, ParseError> {
+        let mut intervals: Vec<Interval> = Vec::new();
+        let mut position: usize = skip_whitespace(raw_range, 0);
+        loop {
+            let lower_inclusive: bool = match raw_range[position..].chars().next() {
+                Some('[') => true,
+                Some('(') => false,
+                Some(character) => return Err(ParseError::UnexpectedCharacter { character, offset: position }),
+                None => return Err(ParseError::EmptyComponent { offset: position }),
+            };
+            let close: usize = match raw_range[position + 1..].find([']', ')']) {
+                Some(index) => position + 1 + index,
+                None => return Err(ParseError::EmptyComponent { offset: raw_range.len() }),
+            };
+            let upper_inclusive: bool = raw_range[close..].starts_with(']');
+            intervals.push(parse_interval(raw_range, position, close, [lower_inclusive, upper_inclusive], &compare)?);
+
+            position = skip_whitespace(raw_range, close + 1);
+            match raw_range[position..].chars().next() {
+                None => break,
+                Some(',') => position = skip_whitespace(raw_range, position + 1),
+                Some(character) => return Err(ParseError::UnexpectedCharacter { character, offset: position }),
+            }
+        }
+        Ok(IntervalRange { intervals })
+    }
+
+    /// Whether `raw_version` is in one of the intervals, ordered by `compare`.
+    pub fn contains<F>(&self, raw_version: &str, compare: F) -> Result<bool, ParseError>
+    where F: Fn(&str, &str) -> Result<Ordering, ParseError> {
+        for interval in self.intervals.iter() {
+            if interval.contains(raw_version, &compare)? {
+                return Ok(true)
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl Interval {
+    pub fn contains<F>(&self, raw_version: &str, compare: F) -> Result<bool, ParseError>
+    where F: Fn(&str, &str) -> Result<Ordering, ParseError> {
+        if let Some(lower) = &self.lower {
+            match compare(raw_version, &lower.version)? {
+                Ordering::Less => return Ok(false),
+                Ordering::Equal if !lower.inclusive => return Ok(false),
+                _ => {},
+            }
+        }
+        if let Some(upper) = &self.upper {
+            match compare(raw_version, &upper.version)? {
+                Ordering::Greater => return Ok(false),
+                Ordering::Equal if !upper.inclusive => return Ok(false),
+                _ => {},
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn skip_whitespace(s: &str, position: usize) -> usize {
+    s.len() - s[position..].trim_start().len()
+}
+
+/// Parses the interval between the brackets at `open` and `close`.
+fn parse_interval<F>(raw_range: &str, open: usize, close: usize, inclusive: [bool; 2], compare: &F) -> Result<Interval, ParseError>
+where F: Fn(&str, &str) -> Result<Ordering, ParseError> {
+    let content: &str = &raw_range[open + 1..close];
+    let parts: Vec<&str> = content.split(',').collect();
+    let bound = |index: usize, part_offset: usize| -> Result<Option<Bound>, ParseError> {
+        let version: &str = parts[index].trim();
+        if version.is_empty() {
+            return Ok(None)
+        }
+        let version_offset: usize = part_offset + parts[index].len() - parts[index].trim_start().len();
+        compare(version, version).map_err(|e| e.shifted(version_offset))?;
+        Ok(Some(Bound { version: version.to_string(), inclusive: inclusive[index] }))
+    };
+
+    match parts.len() {
+        1 => {
+            if let [false, _] | [_, false] = inclusive {
+                let (character, offset): (char, usize) = match inclusive[0] {
+                    false => ('(', open),
+                    true => (')', close),
+                };
+                return Err(ParseError::UnexpectedCharacter { character, offset })
+            }
+            match bound(0, open + 1)? {
+                Some(exact) => Ok(Interval { lower: Some(exact.clone()), upper: Some(exact) }),
+                None => Err(ParseError::EmptyComponent { offset: open + 1 }),
+            }
+        },
+        2 => {
+            let lower: Option<Bound> = bound(0, open + 1)?;
+            let upper: Option<Bound> = bound(1, open + 2 + parts[0].len())?;
+            if let (Some(lower), Some(upper)) = (&lower, &upper) {
+                match compare(&lower.version, &upper.version)? {
+                    Ordering::Greater => return Err(ParseError::EmptyRange { offset: open }),
+                    Ordering::Equal if !(lower.inclusive && upper.inclusive) => return Err(ParseError::EmptyRange { offset: open }),
+                    _ => {},
+                }
+            }
+            Ok(Interval { lower, upper })
+        },
+        _ => Err(ParseError::UnexpectedCharacter { character: ',', offset: open + 2 + parts[0].len() + parts[1].len() }),
+    }
+}
+
+impl fmt::Display for IntervalRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let intervals: Vec<String> = self.intervals.iter().map(|i| i.to_string()).collect();
+        write!(f, "{}", intervals.join(","))
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let (Some(lower), Some(upper)) = (&self.lower, &self.upper) {
+            if lower == upper && lower.inclusive {
+                return write!(f, "[{}]", lower.version)
+            }
+        }
+        let open: char = if self.lower.as_ref().is_some_and(|b| b.inclusive) { '[' } else { '(' };
+        let close: char = if self.upper.as_ref().is_some_and(|b| b.inclusive) { ']' } else { ')' };
+        let lower: &str = self.lower.as_ref().map_or("", |b| b.version.as_str());
+        let upper: &str = self.upper.as_ref().map_or("", |b| b.version.as_str());
+        write!(f, "{}{},{}{}", open, lower, upper, close)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::schemes::{MavenScheme, SemverScheme, VersionScheme};
+
+    fn maven_range(raw_range: &str) -> Result<IntervalRange, ParseError> {
+        IntervalRange::parse(raw_range, |a, b| MavenScheme.compare(a, b))
+    }
+
+    fn assert_contains(raw_range: &str, matching: &[&str], not_matching: &[&str]) {
+        let range: IntervalRange = maven_range(raw_range).unwrap();
+        for raw_version in matching.iter() {
+            assert_eq!(range.contains(raw_version, |a, b| MavenScheme.compare(a, b)), Ok(true), "{} in {}", raw_version, raw_range);
+        }
+        for raw_version in not_matching.iter() {
+            assert_eq!(range.contains(raw_version, |a, b| MavenScheme.compare(a, b)), Ok(false), "{} not in {}", raw_version, raw_range);
+        }
+    }
+
+    // Examples from the Maven POM reference.
+    #[test]
+    fn test_intervals() {
+        assert_contains("[1.0,2.0)", &["1.0", "1.5", "2.0-SNAPSHOT"], &["0.9", "2.0"]);
+        assert_contains("(,1.0]", &["0.1", "1.0"], &["1.0.1"]);
+        assert_contains("[1.2]", &["1.2", "1.2.0"], &["1.2.1", "1.1"]);
+        assert_contains("[1.5,)", &["1.5", "99"], &["1.4"]);
+        assert_contains("(1.0,2.0)", &["1.1"], &["1.0", "2.0"]);
+    }
+
+    #[test]
+    fn test_union() {
+        assert_contains("[1.0,1.2),(1.5,)", &["1.0", "1.1.9", "1.6"], &["1.2", "1.5"]);
+        assert_contains("(,1.0], [1.2,)", &["1.0", "1.2"], &["1.1"]);
+    }
+
+    #[test]
+    fn test_uses_scheme_ordering() {
+        let range: IntervalRange = IntervalRange::parse("[1.0.0,2.0.0)", |a, b| SemverScheme.compare(a, b)).unwrap();
+        assert_eq!(range.contains("2.0.0-rc.1", |a, b| SemverScheme.compare(a, b)), Ok(true));
+        assert!(range.contains("1.5", |a, b| SemverScheme.compare(a, b)).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(maven_range(" [ 1.0 , 2.0 ) ,(,1.0] , [1.2] ").unwrap().to_string(), "[1.0,2.0),(,1.0],[1.2]");
+    }
+
+    #[test]
+    fn test_is_interval_range() {
+        assert!(is_interval_range(" [1.0,2.0)"));
+        assert!(!is_interval_range(">=1.0"));
+    }
+
+    #[test]
+    fn test_invalid_ranges() {
+        assert_eq!(maven_range("1.0"), Err(ParseError::UnexpectedCharacter { character: '1', offset: 0 }));
+        assert_eq!(maven_range("[1.0,2.0"), Err(ParseError::EmptyComponent { offset: 8 }));
+        assert_eq!(maven_range("(1.0)"), Err(ParseError::UnexpectedCharacter { character: '(', offset: 0 }));
+        assert_eq!(maven_range("[1.0)"), Err(ParseError::UnexpectedCharacter { character: ')', offset: 4 }));
+        assert_eq!(maven_range("[1,2,3]"), Err(ParseError::UnexpectedCharacter { character: ',', offset: 4 }));
+        assert_eq!(maven_range("[2.0,1.0]"), Err(ParseError::EmptyRange { offset: 0 }));
+        assert_eq!(maven_range("[1.0,1.0)"), Err(ParseError::EmptyRange { offset: 0 }));
+        assert_eq!(maven_range("[1.0,2.0),"), Err(ParseError::EmptyComponent { offset: 10 }));
+        assert_eq!(maven_range("[1.0,2.0) x"), Err(ParseError::UnexpectedCharacter { character: 'x', offset: 10 }));
+        assert_eq!(maven_range("[]"), Err(ParseError::EmptyComponent { offset: 1 }));
+        let semver_range = IntervalRange::parse("[1.0.0, 1.x)", |a, b| SemverScheme.compare(a, b));
+        assert_eq!(semver_range, Err(ParseError::UnexpectedCharacter { character: 'x', offset: 10 }));
+    }
+}
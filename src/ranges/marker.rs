@@ -0,0 +1,555 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use super::super::errors::ParseError;
+use super::super::schemes::Pep440Version;
+use super::pep440::{Specifier, SpecifierOperator};
+
+/// The variables a marker can name, from PEP 508.
+const VARIABLES: [&str; 12] = [
+    "python_version",
+    "python_full_version",
+    "os_name",
+    "sys_platform",
+    "platform_release",
+    "platform_system",
+    "platform_version",
+    "platform_machine",
+    "platform_python_implementation",
+    "implementation_name",
+    "implementation_version",
+    "extra",
+];
+
+const OPERATORS: [(&str, SpecifierOperator); 8] = [
+    ("===", SpecifierOperator::Arbitrary),
+    ("~=", SpecifierOperator::Compatible),
+    ("==", SpecifierOperator::Equal),
+    ("!=", SpecifierOperator::NotEqual),
+    ("<=", SpecifierOperator::LessOrEqual),
+    (">=", SpecifierOperator::GreaterOrEqual),
+    ("<", SpecifierOperator::Less),
+    (">", SpecifierOperator::Greater),
+];
+
+/// An environment marker, like `python_version >= "3.8" and os_name == "posix"`,
+/// following [PEP 508](https://peps.python.org/pep-0508/#environment-markers).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Marker {
+    Compare { left: MarkerValue, operator: MarkerOperator, right: MarkerValue },
+    And(Box<Marker>, Box<Marker>),
+    Or(Box<Marker>, Box<Marker>),
+}
+
+/// One side of a marker comparison.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MarkerValue {
+    /// A variable looked up in the environment, with its offset in the marker.
+    Variable { name: String, offset: usize },
+    /// A quoted string, without its quotes.
+    Literal(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkerOperator {
+    /// One of the PEP 440 comparison operators, with its offset in the marker.
+    Version { operator: SpecifierOperator, offset: usize },
+    /// `in`
+    In,
+    /// `not in`
+    NotIn,
+}
+
+/// The values of the marker variables, like `python_version` = `3.11`.
+///
+/// `extra` is the empty string unless set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarkerEnvironment {
+    pub values: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    LeftParenthesis,
+    RightParenthesis,
+    Quoted(&'a str),
+    Word(&'a str),
+    Operator(&'a str),
+}
+
+/// Splits `raw_marker` into tokens, each with its byte offset.
+fn tokenize(raw_marker: &str) -> Result<Vec<(Token<'_>, usize)>, ParseError> {
+    let mut tokens: Vec<(Token, usize)> = Vec::new();
+    let mut chars = raw_marker.char_indices().peekable();
+    while let Some((start, character)) = chars.next() {
+        let token: Token = match character {
+            c if c.is_whitespace() => continue,
+            '(' => Token::LeftParenthesis,
+            ')' => Token::RightParenthesis,
+            '"' | '\'' => {
+                let length: usize = match raw_marker[start + 1..].find(character) {
+                    Some(length) => length,
+                    None => return Err(ParseError::EmptyComponent { offset: raw_marker.len() }),
+                };
+                for _ in 0..raw_marker[start + 1..start + 2 + length].chars().count() {
+                    chars.next();
+                }
+                Token::Quoted(&raw_marker[start + 1..start + 1 + length])
+            },
+            c if "<>=!~".contains(c) || c.is_ascii_alphanumeric() || c == '_' || c == '.' => {
+                let is_operator: bool = "<>=!~".contains(c);
+                let belongs = |c: char| match is_operator {
+                    true => "<>=!~".contains(c),
+                    false => c.is_ascii_alphanumeric() || c == '_' || c == '.',
+                };
+                let mut end: usize = start + c.len_utf8();
+                while let Some(&(index, next)) = chars.peek() {
+                    if !belongs(next) {
+                        break
+                    }
+                    end = index + next.len_utf8();
+                    chars.next();
+                }
+                match is_operator {
+                    true => Token::Operator(&raw_marker[start..end]),
+                    false => Token::Word(&raw_marker[start..end]),
+                }
+            },
+            character => return Err(ParseError::UnexpectedCharacter { character, offset: start }),
+        };
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<(Token<'a>, usize)>,
+    position: usize,
+    text: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.position).map(|(token, _)| *token)
+    }
+
+    /// The offset of the next token, or the end of the marker.
+    fn offset(&self) -> usize {
+        self.tokens.get(self.position).map_or(self.text.len(), |(_, offset)| *offset)
+    }
+
+    /// The error for the next token, which is not what the grammar expects.
+    fn unexpected(&self) -> ParseError {
+        match self.tokens.get(self.position) {
+            Some((_, offset)) => {
+                let character: char = self.text[*offset..].chars().next().unwrap_or(' ');
+                ParseError::UnexpectedCharacter { character, offset: *offset }
+            },
+            None => ParseError::EmptyComponent { offset: self.text.len() },
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Marker, ParseError> {
+        let mut marker: Marker = self.parse_and()?;
+        while self.peek() == Some(Token::Word("or")) {
+            self.position += 1;
+            marker = Marker::Or(Box::new(marker), Box::new(self.parse_and()?));
+        }
+        Ok(marker)
+    }
+
+    fn parse_and(&mut self) -> Result<Marker, ParseError> {
+        let mut marker: Marker = self.parse_expression()?;
+        while self.peek() == Some(Token::Word("and")) {
+            self.position += 1;
+            marker = Marker::And(Box::new(marker), Box::new(self.parse_expression()?));
+        }
+        Ok(marker)
+    }
+
+    fn parse_expression(&mut self) -> Result<Marker, ParseError> {
+        if self.peek() == Some(Token::LeftParenthesis) {
+            self.position += 1;
+            let marker: Marker = self.parse_or()?;
+            if self.peek() != Some(Token::RightParenthesis) {
+                return Err(self.unexpected())
+            }
+            self.position += 1;
+            return Ok(marker)
+        }
+        let left: MarkerValue = self.parse_value()?;
+        let operator: MarkerOperator = self.parse_operator()?;
+        let right: MarkerValue = self.parse_value()?;
+        Ok(Marker::Compare { left, operator, right })
+    }
+
+    fn parse_value(&mut self) -> Result<MarkerValue, ParseError> {
+        let offset: usize = self.offset();
+        let value: MarkerValue = match self.peek() {
+            Some(Token::Quoted(literal)) => MarkerValue::Literal(literal.to_string()),
+            Some(Token::Word(name)) if VARIABLES.contains(&name) => MarkerValue::Variable { name: name.to_string(), offset },
+            Some(Token::Word(name)) if name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') => {
+                return Err(ParseError::UnknownVariable { name: name.to_string(), offset })
+            },
+            _ => return Err(self.unexpected()),
+        };
+        self.position += 1;
+        Ok(value)
+    }
+
+    fn parse_operator(&mut self) -> Result<MarkerOperator, ParseError> {
+        let offset: usize = self.offset();
+        let operator: MarkerOperator = match self.peek() {
+            Some(Token::Operator(raw_operator)) => match OPERATORS.iter().find(|(written, _)| *written == raw_operator) {
+                Some((_, operator)) => MarkerOperator::Version { operator: *operator, offset },
+                None => return Err(ParseError::InvalidOperator { operator: raw_operator.to_string(), offset }),
+            },
+            Some(Token::Word("in")) => MarkerOperator::In,
+            Some(Token::Word("not")) => {
+                self.position += 1;
+                if self.peek() != Some(Token::Word("in")) {
+                    return Err(self.unexpected())
+                }
+                MarkerOperator::NotIn
+            },
+            _ => return Err(self.unexpected()),
+        };
+        self.position += 1;
+        Ok(operator)
+    }
+}
+
+impl Marker {
+    pub fn parse(raw_marker: &str) -> Result<Marker, ParseError> {
+        let mut parser: Parser = Parser { tokens: tokenize(raw_marker)?, position: 0, text: raw_marker };
+        let marker: Marker = parser.parse_or()?;
+        if parser.position < parser.tokens.len() {
+            return Err(parser.unexpected())
+        }
+        Ok(marker)
+    }
+
+    /// Evaluates the marker in `environment`.
+    ///
+    /// A comparison whose sides are both PEP 440 versions compares them as
+    /// versions, any other compares them as strings. On strings, `===` is a
+    /// case-insensitive match, and `~=`, which has no string meaning, fails.
+    pub fn evaluate(&self, environment: &MarkerEnvironment) -> Result<bool, ParseError> {
+        match self {
+            Marker::And(left, right) => Ok(left.evaluate(environment)? && right.evaluate(environment)?),
+            Marker::Or(left, right) => Ok(left.evaluate(environment)? || right.evaluate(environment)?),
+            Marker::Compare { left, operator, right } => {
+                let left: &str = left.resolve(environment)?;
+                let right: &str = right.resolve(environment)?;
+                let (operator, offset): (SpecifierOperator, usize) = match *operator {
+                    MarkerOperator::In => return Ok(right.contains(left)),
+                    MarkerOperator::NotIn => return Ok(!right.contains(left)),
+                    MarkerOperator::Version { operator, offset } => (operator, offset),
+                };
+                let written: &str = OPERATORS.iter().find(|(_, found)| *found == operator).map_or("", |(written, _)| written);
+                if let (Ok(version), Ok(specifier)) = (Pep440Version::parse(left), Specifier::parse(&format!("{}{}", written, right))) {
+                    return Ok(specifier.matches(&version))
+                }
+                match operator {
+                    SpecifierOperator::Equal => Ok(left == right),
+                    SpecifierOperator::NotEqual => Ok(left != right),
+                    SpecifierOperator::Less => Ok(left < right),
+                    SpecifierOperator::LessOrEqual => Ok(left <= right),
+                    SpecifierOperator::Greater => Ok(left > right),
+                    SpecifierOperator::GreaterOrEqual => Ok(left >= right),
+                    SpecifierOperator::Arbitrary => Ok(left.eq_ignore_ascii_case(right)),
+                    SpecifierOperator::Compatible => Err(ParseError::InvalidOperator { operator: written.to_string(), offset }),
+                }
+            },
+        }
+    }
+}
+
+impl MarkerValue {
+    fn resolve<'a>(&'a self, environment: &'a MarkerEnvironment) -> Result<&'a str, ParseError> {
+        match self {
+            MarkerValue::Literal(literal) => Ok(literal),
+            MarkerValue::Variable { name, offset } => match environment.values.get(name) {
+                Some(value) => Ok(value),
+                None if name == "extra" => Ok(""),
+                None => Err(ParseError::UnknownVariable { name: name.to_string(), offset: *offset }),
+            },
+        }
+    }
+}
+
+impl MarkerEnvironment {
+    pub fn new() -> MarkerEnvironment {
+        MarkerEnvironment { values: HashMap::new() }
+    }
+
+    pub fn insert(&mut self, name: &str, value: &str) {
+        self.values.insert(name.to_string(), value.to_string());
+    }
+
+    /// Sets a variable from a `name=value` pair.
+    pub fn insert_pair(&mut self, pair: &str) -> Result<(), ParseError> {
+        match pair.find('=') {
+            Some(0) => Err(ParseError::EmptyComponent { offset: 0 }),
+            None => Err(ParseError::EmptyComponent { offset: pair.len() }),
+            Some(index) => {
+                self.insert(pair[..index].trim(), &pair[index + 1..]);
+                Ok(())
+            },
+        }
+    }
+
+    /// Reads a flat JSON object of strings, like `{"python_version": "3.11"}`.
+    /// Numbers are kept as written, so `{"python_version": 3.11}` works too.
+    pub fn from_json(raw_json: &str) -> Result<MarkerEnvironment, ParseError> {
+        let mut reader: JsonReader = JsonReader { text: raw_json, position: 0 };
+        let mut environment: MarkerEnvironment = MarkerEnvironment::new();
+        reader.expect('{')?;
+        if reader.peek() == Some('}') {
+            reader.position += 1;
+        } else {
+            loop {
+                let name: String = reader.read_string()?;
+                reader.expect(':')?;
+                let value: String = match reader.peek() {
+                    Some('"') => reader.read_string()?,
+                    _ => reader.read_number()?,
+                };
+                environment.values.insert(name, value);
+                match reader.peek() {
+                    Some(',') => reader.position += 1,
+                    Some('}') => {
+                        reader.position += 1;
+                        break
+                    },
+                    Some(character) => return Err(ParseError::UnexpectedCharacter { character, offset: reader.position }),
+                    None => return Err(ParseError::EmptyComponent { offset: reader.position }),
+                }
+            }
+        }
+        match reader.peek() {
+            Some(character) => Err(ParseError::UnexpectedCharacter { character, offset: reader.position }),
+            None => Ok(environment),
+        }
+    }
+}
+
+struct JsonReader<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> JsonReader<'a> {
+    /// The next character after whitespace, which is skipped.
+    fn peek(&mut self) -> Option<char> {
+        self.position = self.text.len() - self.text[self.position..].trim_start().len();
+        self.text[self.position..].chars().next()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(character) if character == expected => {
+                self.position += 1;
+                Ok(())
+            },
+            Some(character) => Err(ParseError::UnexpectedCharacter { character, offset: self.position }),
+            None => Err(ParseError::EmptyComponent { offset: self.position }),
+        }
+    }
+
+    fn read_number(&mut self) -> Result<String, ParseError> {
+        let start: usize = self.position;
+        let rest: &str = &self.text[start..];
+        let length: usize = rest.find(|c: char| !(c.is_ascii_digit() || "+-.eE".contains(c))).unwrap_or(rest.len());
+        if length == 0 {
+            return Err(match rest.chars().next() {
+                Some(character) => ParseError::UnexpectedCharacter { character, offset: start },
+                None => ParseError::EmptyComponent { offset: start },
+            })
+        }
+        self.position += length;
+        Ok(rest[..length].to_string())
+    }
+
+    fn read_string(&mut self) -> Result<String, ParseError> {
+        self.expect('"')?;
+        let mut value: String = String::new();
+        let mut chars = self.text[self.position..].char_indices();
+        let start: usize = self.position;
+        while let Some((index, character)) = chars.next() {
+            match character {
+                '"' => {
+                    self.position = start + index + 1;
+                    return Ok(value)
+                },
+                '\\' => {
+                    let escaped: char = match chars.next() {
+                        Some((_, '"')) => '"',
+                        Some((_, '\\')) => '\\',
+                        Some((_, '/')) => '/',
+                        Some((_, 'b')) => '\u{8}',
+                        Some((_, 'f')) => '\u{c}',
+                        Some((_, 'n')) => '\n',
+                        Some((_, 'r')) => '\r',
+                        Some((_, 't')) => '\t',
+                        Some((_, 'u')) => {
+                            let digits: &str = self.text.get(start + index + 2..start + index + 6).unwrap_or("");
+                            let code: Option<char> = u32::from_str_radix(digits, 16).ok().and_then(char::from_u32);
+                            match code {
+                                Some(code) if digits.len() == 4 => {
+                                    for _ in 0..4 {
+                                        chars.next();
+                                    }
+                                    code
+                                },
+                                _ => return Err(ParseError::UnexpectedCharacter { character: 'u', offset: start + index + 1 }),
+                            }
+                        },
+                        Some((offset, character)) => return Err(ParseError::UnexpectedCharacter { character, offset: start + offset }),
+                        None => break,
+                    };
+                    value.push(escaped);
+                },
+                character => value.push(character),
+            }
+        }
+        Err(ParseError::EmptyComponent { offset: self.text.len() })
+    }
+}
+
+impl fmt::Display for Marker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Marker::Compare { left, operator, right } => write!(f, "{} {} {}", left, operator, right),
+            Marker::Or(left, right) => write!(f, "{} or {}", left, right),
+            Marker::And(left, right) => {
+                let side = |marker: &Marker| match marker {
+                    Marker::Or(..) => format!("({})", marker),
+                    _ => marker.to_string(),
+                };
+                write!(f, "{} and {}", side(left), side(right))
+            },
+        }
+    }
+}
+
+impl fmt::Display for MarkerValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MarkerValue::Variable { name, .. } => write!(f, "{}", name),
+            MarkerValue::Literal(literal) if literal.contains('"') => write!(f, "'{}'", literal),
+            MarkerValue::Literal(literal) => write!(f, "\"{}\"", literal),
+        }
+    }
+}
+
+impl fmt::Display for MarkerOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MarkerOperator::Version { operator, .. } => {
+                let written: &str = OPERATORS.iter().find(|(_, found)| found == operator).map_or("", |(written, _)| written);
+                write!(f, "{}", written)
+            },
+            MarkerOperator::In => write!(f, "in"),
+            MarkerOperator::NotIn => write!(f, "not in"),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn environment() -> MarkerEnvironment {
+        let mut environment: MarkerEnvironment = MarkerEnvironment::new();
+        environment.insert("python_version", "3.9");
+        environment.insert("python_full_version", "3.9.0rc1");
+        environment.insert("os_name", "posix");
+        environment.insert("sys_platform", "linux");
+        environment.insert("platform_release", "5.15.0-91-generic");
+        environment.insert("implementation_name", "cpython");
+        environment
+    }
+
+    fn evaluate(raw_marker: &str) -> Result<bool, ParseError> {
+        Marker::parse(raw_marker)?.evaluate(&environment())
+    }
+
+    #[test]
+    fn test_version_comparisons() {
+        assert_eq!(evaluate("python_version >= '3.8'"), Ok(true));
+        // Compared as versions, 3.9 < 3.10 (as strings, "3.9" > "3.10").
+        assert_eq!(evaluate("python_version < '3.10'"), Ok(true));
+        assert_eq!(evaluate("python_version ~= '3.7'"), Ok(true));
+        assert_eq!(evaluate("python_version == '3.*'"), Ok(true));
+        assert_eq!(evaluate("'3.10' > python_version"), Ok(true));
+        assert_eq!(evaluate("python_full_version >= '3.9.0'"), Ok(false));
+        assert_eq!(evaluate("python_full_version >= '3.9.0b1'"), Ok(true));
+    }
+
+    #[test]
+    fn test_string_comparisons() {
+        assert_eq!(evaluate("os_name == 'posix'"), Ok(true));
+        assert_eq!(evaluate("sys_platform != \"win32\""), Ok(true));
+        assert_eq!(evaluate("'linux' in sys_platform"), Ok(true));
+        assert_eq!(evaluate("'win' not in sys_platform"), Ok(true));
+        // Not PEP 440 versions, so "5.15" sorts before "5.4".
+        assert_eq!(evaluate("platform_release >= '5.4.0-1-generic'"), Ok(false));
+        assert_eq!(evaluate("implementation_name === 'CPython'"), Ok(true));
+        assert_eq!(evaluate("os_name ~= 'posix'"), Err(ParseError::InvalidOperator { operator: "~=".to_string(), offset: 8 }));
+    }
+
+    #[test]
+    fn test_boolean_operators() {
+        assert_eq!(evaluate("os_name == 'nt' or python_version > '3' and sys_platform == 'linux'"), Ok(true));
+        assert_eq!(evaluate("(os_name == 'nt' or python_version > '3') and sys_platform == 'win32'"), Ok(false));
+        assert_eq!(evaluate("extra == 'test'"), Ok(false));
+    }
+
+    #[test]
+    fn test_missing_variable() {
+        let error = evaluate("platform_machine == 'x86_64'");
+        assert_eq!(error, Err(ParseError::UnknownVariable { name: "platform_machine".to_string(), offset: 0 }));
+    }
+
+    #[test]
+    fn test_invalid_markers() {
+        assert_eq!(Marker::parse("python_versoin > '3'"), Err(ParseError::UnknownVariable { name: "python_versoin".to_string(), offset: 0 }));
+        assert_eq!(Marker::parse("os_name <> 'nt'"), Err(ParseError::InvalidOperator { operator: "<>".to_string(), offset: 8 }));
+        assert_eq!(Marker::parse("os_name == 'nt"), Err(ParseError::EmptyComponent { offset: 14 }));
+        assert_eq!(Marker::parse("(os_name == 'nt'"), Err(ParseError::EmptyComponent { offset: 16 }));
+        assert_eq!(Marker::parse("os_name == 'nt' and"), Err(ParseError::EmptyComponent { offset: 19 }));
+        assert_eq!(Marker::parse("os_name not 'nt'"), Err(ParseError::UnexpectedCharacter { character: '\'', offset: 12 }));
+        assert_eq!(Marker::parse("os_name == 'nt' os_name"), Err(ParseError::UnexpectedCharacter { character: 'o', offset: 16 }));
+        assert_eq!(Marker::parse("os_name == 'nt' ; x"), Err(ParseError::UnexpectedCharacter { character: ';', offset: 16 }));
+    }
+
+    #[test]
+    fn test_display() {
+        let marker: Marker = Marker::parse("( os_name=='nt' or extra  ==  'a' )and'3'<python_version").unwrap();
+        assert_eq!(marker.to_string(), "(os_name == \"nt\" or extra == \"a\") and \"3\" < python_version");
+    }
+
+    #[test]
+    fn test_environment_from_json() {
+        let json: &str = r#" { "python_version": "3.11", "os_name" : "po\"six!", "implementation_version": 3.11 } "#;
+        let environment: MarkerEnvironment = MarkerEnvironment::from_json(json).unwrap();
+        assert_eq!(environment.values.get("python_version").map(String::as_str), Some("3.11"));
+        assert_eq!(environment.values.get("os_name").map(String::as_str), Some("po\"six!"));
+        assert_eq!(environment.values.get("implementation_version").map(String::as_str), Some("3.11"));
+        assert_eq!(MarkerEnvironment::from_json("{}"), Ok(MarkerEnvironment::new()));
+        assert_eq!(MarkerEnvironment::from_json("{\"a\": true}"), Err(ParseError::UnexpectedCharacter { character: 't', offset: 6 }));
+        assert_eq!(MarkerEnvironment::from_json("{\"a\": \"b\""), Err(ParseError::EmptyComponent { offset: 9 }));
+        assert_eq!(MarkerEnvironment::from_json("{\"a\": \"b\"} x"), Err(ParseError::UnexpectedCharacter { character: 'x', offset: 11 }));
+    }
+
+    #[test]
+    fn test_environment_pairs() {
+        let mut environment: MarkerEnvironment = MarkerEnvironment::new();
+        assert_eq!(environment.insert_pair("python_version=3.12"), Ok(()));
+        assert_eq!(environment.values.get("python_version").map(String::as_str), Some("3.12"));
+        assert_eq!(environment.insert_pair("=3.12"), Err(ParseError::EmptyComponent { offset: 0 }));
+        assert_eq!(environment.insert_pair("python_version"), Err(ParseError::EmptyComponent { offset: 14 }));
+    }
+}
@@ -0,0 +1,13 @@
+mod cargo;
+mod debian;
+mod interval;
+mod marker;
+mod npm;
+mod pep440;
+
+pub use cargo::{CargoComparator, CargoOperator, CargoReq};
+pub use debian::{Dependency, InstalledPackages, Relation, RelationField, RelationOperator};
+pub use interval::{is_interval_range, Bound, Interval, IntervalRange};
+pub use marker::{Marker, MarkerEnvironment, MarkerOperator, MarkerValue};
+pub use npm::{NpmComparator, NpmRange};
+pub use pep440::{Specifier, SpecifierOperator, SpecifierSet};
@@ -0,0 +1,422 @@
+use std::fmt;
+
+use super::super::compare::Operator;
+use super::super::errors::ParseError;
+use super::super::schemes::{parse_pre_release_identifier, split_identifiers, Identifier, SemVer};
+
+/// One `operator version` constraint of an [`NpmRange`], after desugaring.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NpmComparator {
+    pub operator: Operator,
+    pub version: SemVer,
+}
+
+/// An npm range such as `^1.2.3`, `~1.2`, `1.2.x`, `1.0.0 - 2.3.4` or `>=1 <2 || >=3`.
+///
+/// Each `||` alternative is desugared into a set of comparators that must all
+/// match, the way `node-semver` does: `^1.2.3` is `>=1.2.3 <2.0.0-0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NpmRange {
+    pub sets: Vec<Vec<NpmComparator>>,
+}
+
+/// A version with some numbers possibly missing or `x`, like `1.2` or `1.x`.
+#[derive(Debug)]
+struct Partial {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre_release: Vec<Identifier>,
+    build: Vec<String>,
+}
+
+impl NpmRange {
+    pub fn parse(raw_range: &str) -> Result<NpmRange, ParseError> {
+        let mut sets: Vec<Vec<NpmComparator>> = Vec::new();
+        let mut offset: usize = 0;
+        for raw_set in raw_range.split("||") {
+            sets.push(parse_set(raw_set, offset)?);
+            offset += raw_set.len() + 2;
+        }
+        Ok(NpmRange { sets })
+    }
+
+    /// Whether `version` matches one of the `||` alternatives.
+    ///
+    /// As in npm, a pre-release only matches an alternative having a
+    /// pre-release comparator with the same `major.minor.patch`:
+    /// `1.2.4-beta` does not match `>=1.2.3`, but `1.2.3-beta.2` matches `>=1.2.3-beta.1`.
+    pub fn matches(&self, version: &SemVer) -> bool {
+        self.sets.iter().any(|set| set_matches(set, version))
+    }
+}
+
+impl NpmComparator {
+    pub fn matches(&self, version: &SemVer) -> bool {
+        self.operator.matches(version.cmp(&self.version))
+    }
+}
+
+fn set_matches(set: &[NpmComparator], version: &SemVer) -> bool {
+    if !set.iter().all(|comparator| comparator.matches(version)) {
+        return false
+    }
+    if !version.is_prerelease() {
+        return true
+    }
+    set.iter().any(|comparator| {
+        comparator.version.is_prerelease()
+            && [comparator.version.major, comparator.version.minor, comparator.version.patch]
+                == [version.major, version.minor, version.patch]
+    })
+}
+
+/// Splits on whitespace, keeping the offset of each token.
+fn tokens(s: &str, offset: usize) -> Vec<(&str, usize)> {
+    let mut tokens: Vec<(&str, usize)> = Vec::new();
+    let mut start: Option<usize> = None;
+    for (index, c) in s.char_indices().chain(std::iter::once((s.len(), ' '))) {
+        match (c.is_whitespace(), start) {
+            (true, Some(token_start)) => {
+                tokens.push((&s[token_start..index], offset + token_start));
+                start = None;
+            },
+            (false, None) => start = Some(index),
+            _ => {},
+        }
+    }
+    tokens
+}
+
+fn split_operator(token: &str) -> (&str, &str) {
+    let operator_len: usize = token.find(|c| !"<>=~^".contains(c)).unwrap_or(token.len());
+    (&token[..operator_len], &token[operator_len..])
+}
+
+fn parse_set(raw_set: &str, offset: usize) -> Result<Vec<NpmComparator>, ParseError> {
+    let tokens: Vec<(&str, usize)> = tokens(raw_set, offset);
+    if tokens.len() == 3 && tokens[1].0 == "-" {
+        let from: Partial = parse_partial(tokens[0].0, tokens[0].1)?;
+        let to: Partial = parse_partial(tokens[2].0, tokens[2].1)?;
+        return Ok(desugar_hyphen(from, to))
+    }
+
+    let mut comparators: Vec<NpmComparator> = Vec::new();
+    let mut index: usize = 0;
+    while index < tokens.len() {
+        let (token, token_offset): (&str, usize) = tokens[index];
+        let (operator, rest): (&str, &str) = split_operator(token);
+        // `>= 1.2` is read as `>=1.2`
+        let (raw_partial, partial_offset): (&str, usize) = match (rest.is_empty(), tokens.get(index + 1)) {
+            (true, Some(next)) if !operator.is_empty() => {
+                index += 1;
+                *next
+            },
+            _ => (rest, token_offset + operator.len()),
+        };
+        let partial: Partial = parse_partial(raw_partial, partial_offset)?;
+        let desugared: Vec<NpmComparator> = match operator {
+            "~" | "~>" => desugar_tilde(partial),
+            "^" => desugar_caret(partial),
+            "" | "=" | "<" | "<=" | ">" | ">=" => desugar_primitive(operator, partial),
+            _ => return Err(ParseError::InvalidOperator { operator: operator.to_string(), offset: token_offset }),
+        };
+        comparators.extend(desugared);
+        index += 1;
+    }
+    Ok(comparators)
+}
+
+fn parse_partial(raw_partial: &str, offset: usize) -> Result<Partial, ParseError> {
+    let (raw_partial, offset): (&str, usize) = match raw_partial.strip_prefix('v') {
+        Some(rest) => (rest, offset + 1),
+        None => (raw_partial, offset),
+    };
+    let main_len: usize = raw_partial.find(['-', '+']).unwrap_or(raw_partial.len());
+    let raw_main: &str = &raw_partial[..main_len];
+
+    let mut numbers: Vec<Option<u64>> = Vec::new();
+    let mut number_offset: usize = offset;
+    for raw_number in raw_main.split('.') {
+        if numbers.len() == 3 {
+            return Err(ParseError::UnexpectedCharacter { character: '.', offset: number_offset - 1 })
+        }
+        numbers.push(parse_x_number(raw_number, number_offset)?);
+        number_offset += raw_number.len() + 1;
+    }
+    if main_len < raw_partial.len() && numbers.len() < 3 {
+        let character: char = raw_partial[main_len..].chars().next().unwrap_or('-');
+        return Err(ParseError::UnexpectedCharacter { character, offset: offset + main_len })
+    }
+
+    let qualifier: &str = &raw_partial[main_len..];
+    let (raw_pre_release, raw_build): (&str, &str) = match qualifier.find('+') {
+        Some(index) => (&qualifier[..index], &qualifier[index + 1..]),
+        None => (qualifier, ""),
+    };
+    let pre_release: Vec<Identifier> = match raw_pre_release.strip_prefix('-') {
+        Some(s) => split_identifiers(s, offset + main_len + 1)?
+            .into_iter()
+            .map(|(identifier, identifier_offset)| parse_pre_release_identifier(identifier, identifier_offset))
+            .collect::<Result<Vec<Identifier>, ParseError>>()?,
+        None => Vec::new(),
+    };
+    let build: Vec<String> = match qualifier.find('+') {
+        Some(index) => split_identifiers(raw_build, offset + main_len + index + 1)?
+            .into_iter()
+            .map(|(identifier, _)| identifier.to_string())
+            .collect(),
+        None => Vec::new(),
+    };
+
+    // Everything after an `x` is an `x` too.
+    let major: Option<u64> = numbers[0];
+    let minor: Option<u64> = major.and(numbers.get(1).cloned().flatten());
+    let patch: Option<u64> = minor.and(numbers.get(2).cloned().flatten());
+    Ok(Partial { major, minor, patch, pre_release, build })
+}
+
+fn parse_x_number(s: &str, offset: usize) -> Result<Option<u64>, ParseError> {
+    match s {
+        "" => Err(ParseError::EmptyComponent { offset }),
+        "x" | "X" | "*" => Ok(None),
+        _ => {
+            if let Some((index, character)) = s.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+                return Err(ParseError::UnexpectedCharacter { character, offset: offset + index })
+            }
+            if s.len() > 1 && s.starts_with('0') {
+                return Err(ParseError::LeadingZero { offset })
+            }
+            s.parse().map(Some).map_err(|_| ParseError::NumericOverflow { offset })
+        },
+    }
+}
+
+fn version(major: u64, minor: u64, patch: u64) -> SemVer {
+    SemVer { major, minor, patch, pre_release: Vec::new(), build: Vec::new() }
+}
+
+/// `major.minor.patch-0`, the lowest version with these numbers.
+fn lowest(major: u64, minor: u64, patch: u64) -> SemVer {
+    SemVer { pre_release: vec![Identifier::Numeric(0)], ..version(major, minor, patch) }
+}
+
+fn comparator(operator: Operator, version: SemVer) -> NpmComparator {
+    NpmComparator { operator, version }
+}
+
+/// `<0.0.0-0`, which nothing matches.
+fn nothing() -> Vec<NpmComparator> {
+    vec![comparator(Operator::StrictlyLess, lowest(0, 0, 0))]
+}
+
+fn full_version(partial: &Partial, major: u64, minor: u64, patch: u64) -> SemVer {
+    SemVer {
+        pre_release: partial.pre_release.clone(),
+        build: partial.build.clone(),
+        ..version(major, minor, patch)
+    }
+}
+
+fn desugar_primitive(operator: &str, partial: Partial) -> Vec<NpmComparator> {
+    let (major, minor, patch): (u64, Option<u64>, Option<u64>) = match partial.major {
+        Some(major) => (major, partial.minor, partial.patch),
+        None => return match operator {
+            "<" | ">" => nothing(),
+            _ => Vec::new(),
+        },
+    };
+    if let (Some(minor), Some(patch)) = (minor, patch) {
+        let operator: Operator = match operator {
+            "<" => Operator::StrictlyLess,
+            "<=" => Operator::LessOrEqual,
+            ">" => Operator::StrictlyGreater,
+            ">=" => Operator::GreaterOrEqual,
+            _ => Operator::Equal,
+        };
+        return vec![comparator(operator, full_version(&partial, major, minor, patch))]
+    }
+
+    match (operator, minor) {
+        (">", None) => vec![comparator(Operator::GreaterOrEqual, version(major.saturating_add(1), 0, 0))],
+        (">", Some(minor)) => vec![comparator(Operator::GreaterOrEqual, version(major, minor.saturating_add(1), 0))],
+        (">=", minor) => vec![comparator(Operator::GreaterOrEqual, version(major, minor.unwrap_or(0), 0))],
+        ("<", minor) => vec![comparator(Operator::StrictlyLess, lowest(major, minor.unwrap_or(0), 0))],
+        ("<=", None) => vec![comparator(Operator::StrictlyLess, lowest(major.saturating_add(1), 0, 0))],
+        ("<=", Some(minor)) => vec![comparator(Operator::StrictlyLess, lowest(major, minor.saturating_add(1), 0))],
+        (_, None) => vec![
+            comparator(Operator::GreaterOrEqual, version(major, 0, 0)),
+            comparator(Operator::StrictlyLess, lowest(major.saturating_add(1), 0, 0)),
+        ],
+        (_, Some(minor)) => vec![
+            comparator(Operator::GreaterOrEqual, version(major, minor, 0)),
+            comparator(Operator::StrictlyLess, lowest(major, minor.saturating_add(1), 0)),
+        ],
+    }
+}
+
+fn desugar_tilde(partial: Partial) -> Vec<NpmComparator> {
+    match (partial.major, partial.minor, partial.patch) {
+        (None, _, _) => Vec::new(),
+        (Some(major), None, _) => vec![
+            comparator(Operator::GreaterOrEqual, version(major, 0, 0)),
+            comparator(Operator::StrictlyLess, lowest(major.saturating_add(1), 0, 0)),
+        ],
+        (Some(major), Some(minor), patch) => vec![
+            comparator(Operator::GreaterOrEqual, full_version(&partial, major, minor, patch.unwrap_or(0))),
+            comparator(Operator::StrictlyLess, lowest(major, minor.saturating_add(1), 0)),
+        ],
+    }
+}
+
+fn desugar_caret(partial: Partial) -> Vec<NpmComparator> {
+    let (major, minor, patch): (u64, Option<u64>, Option<u64>) = match partial.major {
+        Some(major) => (major, partial.minor, partial.patch),
+        None => return Vec::new(),
+    };
+    // The upper bound increments the first non-zero number.
+    let upper: SemVer = match (major, minor, patch) {
+        (_, None, _) => lowest(major.saturating_add(1), 0, 0),
+        (0, Some(minor), None) => lowest(0, minor.saturating_add(1), 0),
+        (0, Some(0), Some(patch)) => lowest(0, 0, patch.saturating_add(1)),
+        (0, Some(minor), Some(_)) => lowest(0, minor.saturating_add(1), 0),
+        (_, Some(_), _) => lowest(major.saturating_add(1), 0, 0),
+    };
+    vec![
+        comparator(Operator::GreaterOrEqual, full_version(&partial, major, minor.unwrap_or(0), patch.unwrap_or(0))),
+        comparator(Operator::StrictlyLess, upper),
+    ]
+}
+
+fn desugar_hyphen(from: Partial, to: Partial) -> Vec<NpmComparator> {
+    let mut comparators: Vec<NpmComparator> = Vec::new();
+    if let Some(major) = from.major {
+        let lower: SemVer = full_version(&from, major, from.minor.unwrap_or(0), from.patch.unwrap_or(0));
+        comparators.push(comparator(Operator::GreaterOrEqual, lower));
+    }
+    match (to.major, to.minor, to.patch) {
+        (None, _, _) => {},
+        (Some(major), None, _) => comparators.push(comparator(Operator::StrictlyLess, lowest(major.saturating_add(1), 0, 0))),
+        (Some(major), Some(minor), None) => comparators.push(comparator(Operator::StrictlyLess, lowest(major, minor.saturating_add(1), 0))),
+        (Some(major), Some(minor), Some(patch)) => {
+            comparators.push(comparator(Operator::LessOrEqual, full_version(&to, major, minor, patch)));
+        },
+    }
+    comparators
+}
+
+/// Writes the desugared range, like `node-semver`'s `Range#range`.
+impl fmt::Display for NpmRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sets: Vec<String> = self.sets.iter()
+            .map(|set| match set.is_empty() {
+                true => "*".to_string(),
+                false => set.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" "),
+            })
+            .collect();
+        write!(f, "{}", sets.join(" || "))
+    }
+}
+
+impl fmt::Display for NpmComparator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operator: &str = match self.operator {
+            Operator::StrictlyLess => "<",
+            Operator::LessOrEqual => "<=",
+            Operator::Equal | Operator::NotEqual => "",
+            Operator::GreaterOrEqual => ">=",
+            Operator::StrictlyGreater => ">",
+        };
+        write!(f, "{}{}", operator, self.version)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(s: &str) -> NpmRange {
+        NpmRange::parse(s).unwrap()
+    }
+
+    fn satisfies(raw_version: &str, raw_range: &str) -> bool {
+        range(raw_range).matches(&SemVer::parse(raw_version).unwrap())
+    }
+
+    // Desugaring examples from node-semver's README.
+    #[test]
+    fn test_desugar() {
+        let cases = [
+            ("1.2.3 - 2.3.4", ">=1.2.3 <=2.3.4"),
+            ("1.2 - 2.3.4", ">=1.2.0 <=2.3.4"),
+            ("1.2.3 - 2.3", ">=1.2.3 <2.4.0-0"),
+            ("1.2.3 - 2", ">=1.2.3 <3.0.0-0"),
+            ("*", "*"),
+            ("", "*"),
+            ("1.x", ">=1.0.0 <2.0.0-0"),
+            ("1.2.x", ">=1.2.0 <1.3.0-0"),
+            ("1", ">=1.0.0 <2.0.0-0"),
+            ("~1.2.3", ">=1.2.3 <1.3.0-0"),
+            ("~1.2", ">=1.2.0 <1.3.0-0"),
+            ("~1", ">=1.0.0 <2.0.0-0"),
+            ("~0.2.3", ">=0.2.3 <0.3.0-0"),
+            ("~1.2.3-beta.2", ">=1.2.3-beta.2 <1.3.0-0"),
+            ("^1.2.3", ">=1.2.3 <2.0.0-0"),
+            ("^0.2.3", ">=0.2.3 <0.3.0-0"),
+            ("^0.0.3", ">=0.0.3 <0.0.4-0"),
+            ("^1.2.3-beta.2", ">=1.2.3-beta.2 <2.0.0-0"),
+            ("^0.0.3-beta", ">=0.0.3-beta <0.0.4-0"),
+            ("^1.2.x", ">=1.2.0 <2.0.0-0"),
+            ("^0.0.x", ">=0.0.0 <0.1.0-0"),
+            ("^0.0", ">=0.0.0 <0.1.0-0"),
+            ("^1.x", ">=1.0.0 <2.0.0-0"),
+            ("^0.x", ">=0.0.0 <1.0.0-0"),
+            (">1.2", ">=1.3.0"),
+            ("<=1.2", "<1.3.0-0"),
+            ("<1", "<1.0.0-0"),
+            (">*", "<0.0.0-0"),
+            (">= 1.2.3", ">=1.2.3"),
+            (">=1 <2 || >=3", ">=1.0.0 <2.0.0-0 || >=3.0.0"),
+        ];
+        for (raw_range, expected) in cases.iter() {
+            assert_eq!(range(raw_range).to_string(), *expected, "{}", raw_range);
+        }
+    }
+
+    #[test]
+    fn test_satisfies() {
+        assert!(satisfies("1.9.9", "^1.2.3"));
+        assert!(!satisfies("2.0.0", "^1.2.3"));
+        assert!(satisfies("1.2.9", "~1.2"));
+        assert!(!satisfies("1.3.0", "~1.2"));
+        assert!(satisfies("1.2.7", "1.2.x"));
+        assert!(satisfies("2.3.4", "1.0.0 - 2.3.4"));
+        assert!(!satisfies("2.3.5", "1.0.0 - 2.3.4"));
+        assert!(satisfies("1.5.0", ">=1 <2 || >=3"));
+        assert!(!satisfies("2.5.0", ">=1 <2 || >=3"));
+        assert!(satisfies("3.0.0", ">=1 <2 || >=3"));
+        assert!(!satisfies("0.1.0", ">*"));
+    }
+
+    #[test]
+    fn test_prerelease_needs_same_tuple() {
+        assert!(satisfies("1.2.3-alpha.7", ">1.2.3-alpha.3"));
+        assert!(!satisfies("3.4.5-alpha.9", ">1.2.3-alpha.3"));
+        assert!(!satisfies("1.2.4-beta", ">=1.2.3"));
+        assert!(!satisfies("1.0.0-beta", "*"));
+        assert!(satisfies("1.2.3-beta.4", "^1.2.3-beta.2"));
+        assert!(!satisfies("1.2.4-beta.4", "^1.2.3-beta.2"));
+        assert!(satisfies("1.2.3-beta.4", "<1.0.0 || ~1.2.3-beta.1"));
+    }
+
+    #[test]
+    fn test_invalid_ranges() {
+        assert_eq!(NpmRange::parse("=>1.2"), Err(ParseError::InvalidOperator { operator: "=>".to_string(), offset: 0 }));
+        assert_eq!(NpmRange::parse("^1.2.3 || ~01.2"), Err(ParseError::LeadingZero { offset: 11 }));
+        assert_eq!(NpmRange::parse("1.2-beta"), Err(ParseError::UnexpectedCharacter { character: '-', offset: 3 }));
+        assert_eq!(NpmRange::parse("1.2.3.4"), Err(ParseError::UnexpectedCharacter { character: '.', offset: 5 }));
+        assert_eq!(NpmRange::parse(">=1.a"), Err(ParseError::UnexpectedCharacter { character: 'a', offset: 4 }));
+        assert_eq!(NpmRange::parse(">="), Err(ParseError::EmptyComponent { offset: 2 }));
+    }
+}
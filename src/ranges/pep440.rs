@@ -0,0 +1,362 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::super::errors::ParseError;
+use super::super::schemes::Pep440Version;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecifierOperator {
+    /// `~=`
+    Compatible,
+    /// `==`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `<=`
+    LessOrEqual,
+    /// `>=`
+    GreaterOrEqual,
+    /// `<`
+    Less,
+    /// `>`
+    Greater,
+    /// `===`
+    Arbitrary,
+}
+
+/// One clause of a [`SpecifierSet`], like `~=2.2.post3` or `==1.1.*`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Specifier {
+    pub operator: SpecifierOperator,
+    /// The version, `None` for `===` with a string that is not a PEP 440 version.
+    pub version: Option<Pep440Version>,
+    /// Whether the version ends with `.*`, only for `==` and `!=`.
+    pub wildcard: bool,
+    /// The version as written, without `.*`.
+    pub raw_version: String,
+}
+
+/// A comma-separated list of PEP 440 specifiers, like `>=1.0,<2,!=1.5`,
+/// following [PEP 440](https://peps.python.org/pep-0440/#version-specifiers).
+///
+/// Pre-releases are excluded unless `prereleases` is `Some(true)` or, when it
+/// is `None`, a `~=`, `==`, `>=`, `<=` or `===` specifier names a pre-release.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpecifierSet {
+    pub specifiers: Vec<Specifier>,
+    pub prereleases: Option<bool>,
+}
+
+const OPERATORS: [(&str, SpecifierOperator); 8] = [
+    ("===", SpecifierOperator::Arbitrary),
+    ("~=", SpecifierOperator::Compatible),
+    ("==", SpecifierOperator::Equal),
+    ("!=", SpecifierOperator::NotEqual),
+    ("<=", SpecifierOperator::LessOrEqual),
+    (">=", SpecifierOperator::GreaterOrEqual),
+    ("<", SpecifierOperator::Less),
+    (">", SpecifierOperator::Greater),
+];
+
+impl SpecifierSet {
+    /// Parses `raw_specifiers`. An empty string is a set without specifiers.
+    pub fn parse(raw_specifiers: &str) -> Result<SpecifierSet, ParseError> {
+        let mut specifiers: Vec<Specifier> = Vec::new();
+        if !raw_specifiers.trim().is_empty() {
+            let mut offset: usize = 0;
+            for raw_specifier in raw_specifiers.split(',') {
+                specifiers.push(Specifier::parse(raw_specifier).map_err(|e| e.shifted(offset))?);
+                offset += raw_specifier.len() + 1;
+            }
+        }
+        Ok(SpecifierSet { specifiers, prereleases: None })
+    }
+
+    /// Whether pre-releases can match, see [`SpecifierSet`].
+    pub fn allows_prereleases(&self) -> bool {
+        match self.prereleases {
+            Some(prereleases) => prereleases,
+            None => self.specifiers.iter().any(|specifier| specifier.names_prerelease()),
+        }
+    }
+
+    pub fn contains(&self, version: &Pep440Version) -> bool {
+        if version.is_prerelease() && !self.allows_prereleases() {
+            return false
+        }
+        self.specifiers.iter().all(|specifier| specifier.matches(version))
+    }
+
+    /// Like [`SpecifierSet::contains`], for a raw version.
+    ///
+    /// `===` compares the raw strings, so a version that is not valid PEP 440
+    /// can still match a set made only of `===` specifiers.
+    pub fn contains_str(&self, raw_version: &str) -> Result<bool, ParseError> {
+        let arbitrary_only: bool = !self.specifiers.is_empty()
+            && self.specifiers.iter().all(|specifier| specifier.operator == SpecifierOperator::Arbitrary);
+        let version: Pep440Version = match Pep440Version::parse(raw_version) {
+            Ok(version) => version,
+            Err(_) if arbitrary_only => {
+                return Ok(self.specifiers.iter().all(|specifier| specifier.matches_arbitrary(raw_version)))
+            },
+            Err(error) => return Err(error),
+        };
+        if version.is_prerelease() && !self.allows_prereleases() {
+            return Ok(false)
+        }
+        Ok(self.specifiers.iter().all(|specifier| match specifier.operator {
+            SpecifierOperator::Arbitrary => specifier.matches_arbitrary(raw_version),
+            _ => specifier.matches(&version),
+        }))
+    }
+}
+
+impl Specifier {
+    pub fn parse(raw_specifier: &str) -> Result<Specifier, ParseError> {
+        let operator_start: usize = raw_specifier.len() - raw_specifier.trim_start().len();
+        let trimmed: &str = raw_specifier.trim();
+        let (prefix, operator): (&str, SpecifierOperator) = match OPERATORS.iter().find(|(prefix, _)| trimmed.starts_with(prefix)) {
+            Some(found) => *found,
+            None => {
+                let operator_len: usize = trimmed.find(|c| !"<>=!~".contains(c)).unwrap_or(trimmed.len());
+                let operator: String = trimmed[..operator_len].to_string();
+                return Err(ParseError::InvalidOperator { operator, offset: operator_start })
+            },
+        };
+        let raw_version: &str = trimmed[prefix.len()..].trim_start();
+        let version_start: usize = operator_start + trimmed.len() - raw_version.len();
+        if raw_version.is_empty() {
+            return Err(ParseError::EmptyComponent { offset: version_start })
+        }
+
+        if operator == SpecifierOperator::Arbitrary {
+            if let Some((index, character)) = raw_version.char_indices().find(|(_, c)| c.is_whitespace()) {
+                return Err(ParseError::UnexpectedCharacter { character, offset: version_start + index })
+            }
+            return Ok(Specifier {
+                operator,
+                version: Pep440Version::parse(raw_version).ok(),
+                wildcard: false,
+                raw_version: raw_version.to_string(),
+            })
+        }
+
+        let (raw_version, wildcard): (&str, bool) = match raw_version.strip_suffix(".*") {
+            Some(prefix) => (prefix, true),
+            None => (raw_version, false),
+        };
+        let version: Pep440Version = Pep440Version::parse(raw_version).map_err(|e| e.shifted(version_start))?;
+        let wildcard_allowed: bool = operator == SpecifierOperator::Equal || operator == SpecifierOperator::NotEqual;
+        if wildcard && (!wildcard_allowed || version.dev.is_some() || !version.local.is_empty()) {
+            return Err(ParseError::UnexpectedCharacter { character: '*', offset: version_start + raw_version.len() + 1 })
+        }
+        if !version.local.is_empty() && !wildcard_allowed {
+            let index: usize = raw_version.find('+').unwrap_or(0);
+            return Err(ParseError::UnexpectedCharacter { character: '+', offset: version_start + index })
+        }
+        if operator == SpecifierOperator::Compatible && version.release.len() < 2 {
+            return Err(ParseError::EmptyComponent { offset: version_start + raw_version.len() })
+        }
+        Ok(Specifier { operator, version: Some(version), wildcard, raw_version: raw_version.to_string() })
+    }
+
+    /// Whether this specifier asks for pre-releases by naming one.
+    fn names_prerelease(&self) -> bool {
+        match self.operator {
+            SpecifierOperator::Compatible | SpecifierOperator::Equal | SpecifierOperator::LessOrEqual
+            | SpecifierOperator::GreaterOrEqual | SpecifierOperator::Arbitrary => {
+                self.version.as_ref().is_some_and(|version| version.is_prerelease())
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether `version` matches, pre-releases aside.
+    pub fn matches(&self, version: &Pep440Version) -> bool {
+        let spec: &Pep440Version = match &self.version {
+            Some(spec) => spec,
+            None => return self.matches_arbitrary(&version.to_string()),
+        };
+        match self.operator {
+            SpecifierOperator::Compatible => {
+                let prefix: &[u64] = &spec.release[..spec.release.len() - 1];
+                public(version) >= *spec && version.epoch == spec.epoch && release_starts_with(version, prefix)
+            },
+            SpecifierOperator::Equal => self.matches_equal(version, spec),
+            SpecifierOperator::NotEqual => !self.matches_equal(version, spec),
+            SpecifierOperator::LessOrEqual => public(version) <= *spec,
+            SpecifierOperator::GreaterOrEqual => public(version) >= *spec,
+            // `<1.0` excludes `1.0rc1`, `>1.0` excludes `1.0.post1` and `1.0+local`.
+            SpecifierOperator::Less => {
+                version < spec && !(version.is_prerelease() && !spec.is_prerelease() && same_base(version, spec))
+            },
+            SpecifierOperator::Greater => {
+                version > spec
+                    && !(version.post.is_some() && spec.post.is_none() && same_base(version, spec))
+                    && (version.local.is_empty() || !same_base(version, spec))
+            },
+            SpecifierOperator::Arbitrary => self.matches_arbitrary(&version.to_string()),
+        }
+    }
+
+    fn matches_arbitrary(&self, raw_version: &str) -> bool {
+        raw_version.trim().eq_ignore_ascii_case(&self.raw_version)
+    }
+
+    fn matches_equal(&self, version: &Pep440Version, spec: &Pep440Version) -> bool {
+        if self.wildcard {
+            return version.epoch == spec.epoch && prefix_matches(version, spec)
+        }
+        match spec.local.is_empty() {
+            true => public(version) == *spec,
+            false => version == spec,
+        }
+    }
+}
+
+/// The version without its local label.
+fn public(version: &Pep440Version) -> Pep440Version {
+    Pep440Version { local: Vec::new(), ..version.clone() }
+}
+
+fn same_base(a: &Pep440Version, b: &Pep440Version) -> bool {
+    let base = |v: &Pep440Version| Pep440Version { epoch: v.epoch, release: v.release.clone(), pre: None, post: None, dev: None, local: Vec::new() };
+    base(a).cmp(&base(b)) == Ordering::Equal
+}
+
+/// Whether the release, padded with zeros, starts with `prefix`.
+fn release_starts_with(version: &Pep440Version, prefix: &[u64]) -> bool {
+    prefix.iter().enumerate().all(|(index, n)| version.release.get(index).unwrap_or(&0) == n)
+}
+
+/// `==1.1.*` matching: the release, padded with zeros, then the pre and post
+/// releases must start like the prefix.
+fn prefix_matches(version: &Pep440Version, prefix: &Pep440Version) -> bool {
+    if !release_starts_with(version, &prefix.release) {
+        return false
+    }
+    let prefix_suffixes: Vec<String> = suffixes(prefix);
+    if prefix_suffixes.is_empty() {
+        return true
+    }
+    version.release.len() <= prefix.release.len() && suffixes(version).starts_with(&prefix_suffixes)
+}
+
+fn suffixes(version: &Pep440Version) -> Vec<String> {
+    let mut suffixes: Vec<String> = Vec::new();
+    if let Some((kind, number)) = version.pre {
+        suffixes.push(format!("{}{}", kind, number));
+    }
+    if let Some(post) = version.post {
+        suffixes.push(format!("post{}", post));
+    }
+    if let Some(dev) = version.dev {
+        suffixes.push(format!("dev{}", dev));
+    }
+    suffixes
+}
+
+impl fmt::Display for SpecifierSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let specifiers: Vec<String> = self.specifiers.iter().map(|s| s.to_string()).collect();
+        write!(f, "{}", specifiers.join(","))
+    }
+}
+
+impl fmt::Display for Specifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operator: &str = OPERATORS.iter()
+            .find(|(_, operator)| *operator == self.operator)
+            .map_or("", |(prefix, _)| prefix);
+        match &self.version {
+            Some(version) if self.operator != SpecifierOperator::Arbitrary => write!(f, "{}{}", operator, version)?,
+            _ => write!(f, "{}{}", operator, self.raw_version)?,
+        }
+        if self.wildcard {
+            write!(f, ".*")?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contains(raw_specifiers: &str, raw_version: &str) -> bool {
+        SpecifierSet::parse(raw_specifiers).unwrap().contains_str(raw_version).unwrap()
+    }
+
+    fn assert_contains(raw_specifiers: &str, matching: &[&str], not_matching: &[&str]) {
+        for raw_version in matching.iter() {
+            assert!(contains(raw_specifiers, raw_version), "{} in {}", raw_version, raw_specifiers);
+        }
+        for raw_version in not_matching.iter() {
+            assert!(!contains(raw_specifiers, raw_version), "{} not in {}", raw_version, raw_specifiers);
+        }
+    }
+
+    // Examples from PEP 440.
+    #[test]
+    fn test_compatible_release() {
+        assert_contains("~=2.2", &["2.2", "2.3", "2.9.1"], &["2.1", "3.0"]);
+        assert_contains("~=1.4.5", &["1.4.5", "1.4.9"], &["1.5.0", "1.4.4"]);
+        assert_contains("~=2.2.post3", &["2.2.post3", "2.9"], &["2.2", "2.2.post2", "3.0"]);
+        assert_contains("~=1.4.5a4", &["1.4.5a4", "1.4.5", "1.4.9"], &["1.5.0", "1.4.5a3"]);
+    }
+
+    #[test]
+    fn test_version_matching() {
+        assert_contains("==1.1", &["1.1", "1.1.0", "1.1+local"], &["1.1.post1", "1.1.1"]);
+        assert_contains("==1.1.*", &["1.1", "1.1.0", "1.1.post1", "1.1.5"], &["1.10", "1.2"]);
+        assert_contains("==1.1.post1.*", &["1.1.post1"], &["1.1.0.post1", "1.1.post2"]);
+        assert_contains("==1.1+abc", &["1.1+abc"], &["1.1", "1.1+def"]);
+        assert_contains("!=1.5", &["1.4", "1.5.1", "1.5.post1"], &["1.5", "1.5.0"]);
+        assert_contains("!=1.1.*", &["1.2", "1.10"], &["1.1.1"]);
+    }
+
+    #[test]
+    fn test_ordered_comparison() {
+        assert_contains(">=1.0,<2", &["1.0", "1.9.9", "1.5+local"], &["0.9", "2.0"]);
+        assert_contains("<2.0", &["1.9"], &["2.0", "2.0.post1"]);
+        assert_contains(">1.7", &["1.7.1", "1.8"], &["1.7", "1.7.post2", "1.7+local"]);
+        assert_contains(">1.7.post2", &["1.7.post3", "1.8"], &["1.7.post2"]);
+        assert_contains("<=1.0", &["1.0", "1.0+local"], &["1.0.post1"]);
+    }
+
+    #[test]
+    fn test_arbitrary_equality() {
+        assert_contains("===foobar", &["foobar", "FooBar"], &["foobaz"]);
+        assert_contains("===1.0", &["1.0"], &["1.0.0"]);
+    }
+
+    #[test]
+    fn test_prereleases_excluded_unless_requested() {
+        assert_contains("<2.0", &["1.9"], &["2.0a1", "1.9rc1", "1.9.dev1"]);
+        assert_contains(">=1.0", &["1.1"], &["1.1a1"]);
+        assert_contains(">=1.0a1", &["1.0a1", "1.1a1", "1.0"], &["1.0.dev1"]);
+        assert_contains("", &["1.0"], &["1.0a1"]);
+
+        let mut specifiers: SpecifierSet = SpecifierSet::parse(">=1.0").unwrap();
+        specifiers.prereleases = Some(true);
+        assert!(specifiers.contains(&Pep440Version::parse("1.1a1").unwrap()));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(SpecifierSet::parse(" >= 1.0-ALPHA-1 , ==1.1.* ").unwrap().to_string(), ">=1.0a1,==1.1.*");
+        assert_eq!(SpecifierSet::parse("===FooBar").unwrap().to_string(), "===FooBar");
+    }
+
+    #[test]
+    fn test_invalid_specifiers() {
+        assert_eq!(SpecifierSet::parse("1.0"), Err(ParseError::InvalidOperator { operator: "".to_string(), offset: 0 }));
+        assert_eq!(SpecifierSet::parse("=>1.0"), Err(ParseError::InvalidOperator { operator: "=>".to_string(), offset: 0 }));
+        assert_eq!(SpecifierSet::parse(">=1.0, <2.*"), Err(ParseError::UnexpectedCharacter { character: '*', offset: 10 }));
+        assert_eq!(SpecifierSet::parse("~=1"), Err(ParseError::EmptyComponent { offset: 3 }));
+        assert_eq!(SpecifierSet::parse(">=1.0+local"), Err(ParseError::UnexpectedCharacter { character: '+', offset: 5 }));
+        assert_eq!(SpecifierSet::parse("==1.0.dev1.*"), Err(ParseError::UnexpectedCharacter { character: '*', offset: 11 }));
+        assert_eq!(SpecifierSet::parse(">=1.x"), Err(ParseError::UnexpectedCharacter { character: '.', offset: 3 }));
+    }
+}
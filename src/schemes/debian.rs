@@ -0,0 +1,308 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use super::super::errors::ParseError;
+use super::VersionScheme;
+
+/// A Debian package version, `[epoch:]upstream_version[-debian_revision]`,
+/// ordered like `dpkg --compare-versions`.
+#[derive(Debug, Clone, Eq)]
+pub struct DebianVersion {
+    /// `0` when the version has no epoch.
+    pub epoch: u32,
+    pub upstream: String,
+    /// Empty when the version has no hyphen.
+    pub revision: String,
+}
+
+/// Debian package versions, ordered like `dpkg --compare-versions`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DebianScheme;
+
+impl VersionScheme for DebianScheme {
+    type Version = DebianVersion;
+
+    fn parse(&self, raw_version: &str) -> Result<DebianVersion, ParseError> {
+        DebianVersion::parse(raw_version)
+    }
+}
+
+impl DebianVersion {
+    pub fn parse(raw_version: &str) -> Result<DebianVersion, ParseError> {
+        let (epoch, upstream_offset): (u32, usize) = match raw_version.find(':') {
+            Some(index) => (parse_epoch(&raw_version[..index])?, index + 1),
+            None => (0, 0),
+        };
+        let tail: &str = &raw_version[upstream_offset..];
+        let (upstream, revision): (&str, &str) = match tail.rfind('-') {
+            Some(index) => {
+                if index + 1 == tail.len() {
+                    return Err(ParseError::EmptyComponent { offset: upstream_offset + tail.len() })
+                }
+                (&tail[..index], &tail[index + 1..])
+            },
+            None => (tail, ""),
+        };
+
+        match upstream.chars().next() {
+            None => return Err(ParseError::EmptyComponent { offset: upstream_offset }),
+            Some(c) if !c.is_ascii_digit() => {
+                return Err(ParseError::UnexpectedCharacter { character: c, offset: upstream_offset })
+            },
+            Some(_) => {},
+        }
+        check_characters(upstream, upstream_offset, "+-.~")?;
+        check_characters(revision, upstream_offset + upstream.len() + 1, "+.~")?;
+
+        Ok(DebianVersion {
+            epoch,
+            upstream: upstream.to_string(),
+            revision: revision.to_string(),
+        })
+    }
+}
+
+fn parse_epoch(raw_epoch: &str) -> Result<u32, ParseError> {
+    if raw_epoch.is_empty() {
+        return Err(ParseError::EmptyComponent { offset: 0 })
+    }
+    if let Some((index, character)) = raw_epoch.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        return Err(ParseError::UnexpectedCharacter { character, offset: index })
+    }
+    raw_epoch.parse().map_err(|_| ParseError::NumericOverflow { offset: 0 })
+}
+
+fn check_characters(s: &str, offset: usize, allowed_symbols: &str) -> Result<(), ParseError> {
+    match s.char_indices().find(|(_, c)| !(c.is_ascii_alphanumeric() || allowed_symbols.contains(*c))) {
+        Some((index, character)) => Err(ParseError::UnexpectedCharacter { character, offset: offset + index }),
+        None => Ok(()),
+    }
+}
+
+/// Weight of a non-digit character in dpkg's `verrevcmp`:
+/// `~` sorts before the end of the string, which sorts before letters,
+/// which sort before other symbols.
+fn order(c: Option<u8>) -> i32 {
+    match c {
+        None => 0,
+        Some(b'~') => -1,
+        Some(c) if c.is_ascii_digit() => 0,
+        Some(c) if c.is_ascii_alphabetic() => c as i32,
+        Some(c) => c as i32 + 256,
+    }
+}
+
+/// dpkg's `verrevcmp`: alternately compares non-digit runs with [`order`]
+/// and digit runs numerically.
+fn verrevcmp(a: &str, b: &str) -> Ordering {
+    let a: &[u8] = a.as_bytes();
+    let b: &[u8] = b.as_bytes();
+    let (mut i, mut j): (usize, usize) = (0, 0);
+    let is_digit = |s: &[u8], k: usize| s.get(k).is_some_and(|c| c.is_ascii_digit());
+
+    while i < a.len() || j < b.len() {
+        while (i < a.len() && !is_digit(a, i)) || (j < b.len() && !is_digit(b, j)) {
+            let order_a: i32 = order(a.get(i).cloned());
+            let order_b: i32 = order(b.get(j).cloned());
+            if order_a != order_b {
+                return order_a.cmp(&order_b)
+            }
+            i += 1;
+            j += 1;
+        }
+        while a.get(i) == Some(&b'0') {
+            i += 1;
+        }
+        while b.get(j) == Some(&b'0') {
+            j += 1;
+        }
+        let mut first_diff: Ordering = Ordering::Equal;
+        while is_digit(a, i) && is_digit(b, j) {
+            if first_diff == Ordering::Equal {
+                first_diff = a[i].cmp(&b[j]);
+            }
+            i += 1;
+            j += 1;
+        }
+        if is_digit(a, i) {
+            return Ordering::Greater
+        }
+        if is_digit(b, j) {
+            return Ordering::Less
+        }
+        if first_diff != Ordering::Equal {
+            return first_diff
+        }
+    }
+    Ordering::Equal
+}
+
+/// Non-digit and digit runs of `s`, without the leading zeros `verrevcmp` ignores.
+///
+/// Two strings are equal for `verrevcmp` exactly when their runs are equal.
+fn normalized_runs(s: &str) -> Vec<(&str, &str)> {
+    let mut runs: Vec<(&str, &str)> = Vec::new();
+    let mut rest: &str = s;
+    while !rest.is_empty() {
+        let digits_start: usize = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+        let digits_end: usize = rest[digits_start..].find(|c: char| !c.is_ascii_digit()).map_or(rest.len(), |i| digits_start + i);
+        runs.push((&rest[..digits_start], rest[digits_start..digits_end].trim_start_matches('0')));
+        rest = &rest[digits_end..];
+    }
+    if runs == [("", "")] {
+        runs.clear();
+    }
+    runs
+}
+
+impl PartialOrd for DebianVersion {
+    fn partial_cmp(&self, other: &DebianVersion) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DebianVersion {
+    fn cmp(&self, other: &DebianVersion) -> Ordering {
+        let order: Ordering = self.epoch.cmp(&other.epoch);
+        if order != Ordering::Equal {
+            return order
+        }
+
+        let order: Ordering = verrevcmp(&self.upstream, &other.upstream);
+        if order != Ordering::Equal {
+            return order
+        }
+
+        verrevcmp(&self.revision, &other.revision)
+    }
+}
+
+impl PartialEq for DebianVersion {
+    fn eq(&self, other: &DebianVersion) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Hash for DebianVersion {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.epoch.hash(state);
+        normalized_runs(&self.upstream).hash(state);
+        normalized_runs(&self.revision).hash(state);
+    }
+}
+
+impl FromStr for DebianVersion {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<DebianVersion, ParseError> {
+        DebianVersion::parse(s)
+    }
+}
+
+impl fmt::Display for DebianVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.epoch != 0 {
+            write!(f, "{}:", self.epoch)?;
+        }
+        write!(f, "{}", self.upstream)?;
+        if !self.revision.is_empty() {
+            write!(f, "-{}", self.revision)?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
+    use super::*;
+
+    fn debian(s: &str) -> DebianVersion {
+        DebianVersion::parse(s).unwrap()
+    }
+
+    fn hash_of(version: &DebianVersion) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        version.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // Pairs checked with `dpkg --compare-versions a lt b`.
+    const LESS_THAN: [(&str, &str); 22] = [
+        ("1.0", "1.1"),
+        ("1.0~rc1", "1.0"),
+        ("1.0~~", "1.0~~a"),
+        ("1.0~~a", "1.0~"),
+        ("1.0~", "1.0"),
+        ("1.0", "1.0a"),
+        ("2.0", "1:1.0"),
+        ("1.0-1", "1.0-2"),
+        ("1.0-1", "1.0.1-1"),
+        ("1.0-1", "1.0-1.1"),
+        ("1.2.3-4", "1.2.3-4+deb10u1"),
+        ("7.6-0", "7.6p2-4"),
+        ("1.0-1", "1.0.3-3"),
+        ("1.2.2", "1.3"),
+        ("1.2.2-2", "1.3"),
+        ("0-pre", "0-pree"),
+        ("1.1.6r-1", "1.1.6r2-2"),
+        ("2.6b-2", "2.6b2-1"),
+        ("98.1p5-1", "98.1-pre2-b6-2"),
+        ("0.4-1", "0.4a6-2"),
+        ("1.9", "1.10"),
+        ("2.31-13+deb11u5", "2.36-9+deb12u1"),
+    ];
+
+    // Pairs checked with `dpkg --compare-versions a eq b`.
+    const EQUAL: [(&str, &str); 6] = [
+        ("1.0", "1.0"),
+        ("0:1.0", "1.0"),
+        ("1.0", "1.0-0"),
+        ("1.01", "1.1"),
+        ("1.0a", "1.0a0"),
+        ("1.2-3", "1.2-03"),
+    ];
+
+    #[test]
+    fn test_known_dpkg_orderings() {
+        for (lesser, greater) in LESS_THAN.iter() {
+            assert_eq!(debian(lesser).cmp(&debian(greater)), Ordering::Less, "{} < {}", lesser, greater);
+            assert_eq!(debian(greater).cmp(&debian(lesser)), Ordering::Greater, "{} > {}", greater, lesser);
+        }
+    }
+
+    #[test]
+    fn test_known_dpkg_equalities() {
+        for (a, b) in EQUAL.iter() {
+            assert_eq!(debian(a), debian(b), "{} == {}", a, b);
+            assert_eq!(hash_of(&debian(a)), hash_of(&debian(b)), "{} == {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_revision_after_last_hyphen() {
+        let version: DebianVersion = debian("1:2.0-beta-3ubuntu1");
+
+        assert_eq!(version.epoch, 1);
+        assert_eq!(version.upstream, "2.0-beta");
+        assert_eq!(version.revision, "3ubuntu1");
+        assert_eq!(version.to_string(), "1:2.0-beta-3ubuntu1");
+    }
+
+    #[test]
+    fn test_invalid_versions() {
+        assert_eq!(DebianVersion::parse("a:1.0"), Err(ParseError::UnexpectedCharacter { character: 'a', offset: 0 }));
+        assert_eq!(DebianVersion::parse(":1.0"), Err(ParseError::EmptyComponent { offset: 0 }));
+        assert_eq!(DebianVersion::parse("1:"), Err(ParseError::EmptyComponent { offset: 2 }));
+        assert_eq!(DebianVersion::parse("1.0-"), Err(ParseError::EmptyComponent { offset: 4 }));
+        assert_eq!(DebianVersion::parse("abc"), Err(ParseError::UnexpectedCharacter { character: 'a', offset: 0 }));
+        assert_eq!(DebianVersion::parse("1.0_1"), Err(ParseError::UnexpectedCharacter { character: '_', offset: 3 }));
+        assert_eq!(DebianVersion::parse("1:1.0:1"), Err(ParseError::UnexpectedCharacter { character: ':', offset: 5 }));
+        assert_eq!(DebianVersion::parse("1.0-1_2"), Err(ParseError::UnexpectedCharacter { character: '_', offset: 5 }));
+        assert_eq!(DebianVersion::parse("99999999999:1"), Err(ParseError::NumericOverflow { offset: 0 }));
+    }
+}
@@ -0,0 +1,361 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use super::super::errors::ParseError;
+use super::VersionScheme;
+
+/// A Maven artifact version, ordered like Maven's `ComparableVersion`.
+///
+/// The version is split into a tree of [`Item`]s: `.` separates items of a
+/// list, `-` and a switch between digits and letters start a sub-list.
+#[derive(Debug, Clone, Eq)]
+pub struct MavenVersion {
+    pub items: Vec<Item>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Item {
+    /// Digits, without leading zeros.
+    Int(String),
+    /// Lowercased qualifier, with `a`, `b`, `m`, `cr`, `ga`, `final` and `release` aliases resolved.
+    Str(String),
+    List(Vec<Item>),
+}
+
+/// Maven `ComparableVersion` ordering.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MavenScheme;
+
+impl VersionScheme for MavenScheme {
+    type Version = MavenVersion;
+
+    fn parse(&self, raw_version: &str) -> Result<MavenVersion, ParseError> {
+        MavenVersion::parse(raw_version)
+    }
+}
+
+/// Known qualifiers, from oldest to newest. `""` is the release itself.
+const QUALIFIERS: [&str; 7] = ["alpha", "beta", "milestone", "rc", "snapshot", "", "sp"];
+
+impl MavenVersion {
+    pub fn parse(raw_version: &str) -> Result<MavenVersion, ParseError> {
+        if raw_version.is_empty() {
+            return Err(ParseError::EmptyComponent { offset: 0 })
+        }
+        if let Some((offset, character)) = raw_version.char_indices().find(|(_, c)| !c.is_ascii_graphic()) {
+            return Err(ParseError::UnexpectedCharacter { character, offset })
+        }
+        let version: String = raw_version.to_ascii_lowercase();
+        let bytes: &[u8] = version.as_bytes();
+
+        // Each new list is nested in the previous one, so the innermost
+        // list is always the one being filled.
+        let mut stack: Vec<Vec<Item>> = vec![Vec::new()];
+        let mut is_digit: bool = false;
+        let mut start: usize = 0;
+        for (i, c) in bytes.iter().enumerate() {
+            match c {
+                b'.' | b'-' => {
+                    let item: Item = match i == start {
+                        true => Item::Int("0".to_string()),
+                        false => parse_item(is_digit, &version[start..i]),
+                    };
+                    current(&mut stack).push(item);
+                    start = i + 1;
+                    if *c == b'-' {
+                        stack.push(Vec::new());
+                    }
+                },
+                c if c.is_ascii_digit() => {
+                    if !is_digit && i > start {
+                        // 1.0.0.X1 < 1.0.0-X2: treat .X as -X for any string qualifier X
+                        if !current(&mut stack).is_empty() {
+                            stack.push(Vec::new());
+                        }
+                        current(&mut stack).push(string_item(&version[start..i], true));
+                        start = i;
+                        stack.push(Vec::new());
+                    }
+                    is_digit = true;
+                },
+                _ => {
+                    if is_digit && i > start {
+                        current(&mut stack).push(parse_item(true, &version[start..i]));
+                        start = i;
+                        stack.push(Vec::new());
+                    }
+                    is_digit = false;
+                },
+            }
+        }
+        if bytes.len() > start {
+            if !is_digit && !current(&mut stack).is_empty() {
+                stack.push(Vec::new());
+            }
+            current(&mut stack).push(parse_item(is_digit, &version[start..]));
+        }
+
+        while stack.len() > 1 {
+            let mut list: Vec<Item> = stack.pop().unwrap_or_default();
+            normalize(&mut list);
+            current(&mut stack).push(Item::List(list));
+        }
+        let mut items: Vec<Item> = stack.pop().unwrap_or_default();
+        normalize(&mut items);
+        Ok(MavenVersion { items })
+    }
+}
+
+fn current(stack: &mut [Vec<Item>]) -> &mut Vec<Item> {
+    let last: usize = stack.len() - 1;
+    &mut stack[last]
+}
+
+fn parse_item(is_digit: bool, s: &str) -> Item {
+    match is_digit {
+        true => {
+            let digits: &str = s.trim_start_matches('0');
+            Item::Int(if digits.is_empty() { "0".to_string() } else { digits.to_string() })
+        },
+        false => string_item(s, false),
+    }
+}
+
+fn string_item(s: &str, followed_by_digit: bool) -> Item {
+    let value: &str = match (s, followed_by_digit) {
+        ("a", true) => "alpha",
+        ("b", true) => "beta",
+        ("m", true) => "milestone",
+        _ => s,
+    };
+    let value: &str = match value {
+        "ga" | "final" | "release" => "",
+        "cr" => "rc",
+        _ => value,
+    };
+    Item::Str(value.to_string())
+}
+
+/// Removes trailing null items (`0`, `""`, empty lists), looking through sub-lists.
+fn normalize(list: &mut Vec<Item>) {
+    let mut i: usize = list.len();
+    while i > 0 {
+        i -= 1;
+        if list[i].is_null() {
+            list.remove(i);
+        } else if let Item::List(_) = list[i] {
+            continue
+        } else {
+            break
+        }
+    }
+}
+
+/// Qualifier key compared as a string, as Maven does: known qualifiers by
+/// index, unknown ones after all of them, in lexical order.
+fn comparable_qualifier(qualifier: &str) -> String {
+    match QUALIFIERS.iter().position(|q| *q == qualifier) {
+        Some(index) => index.to_string(),
+        None => format!("{}-{}", QUALIFIERS.len(), qualifier),
+    }
+}
+
+fn cmp_digits(a: &str, b: &str) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+impl Item {
+    fn is_null(&self) -> bool {
+        match self {
+            Item::Int(digits) => digits == "0",
+            Item::Str(value) => value.is_empty(),
+            Item::List(items) => items.is_empty(),
+        }
+    }
+
+    /// Compares to `other`, a missing item being a padding null.
+    fn compare_to(&self, other: Option<&Item>) -> Ordering {
+        match (self, other) {
+            (Item::Int(digits), None) => if digits == "0" { Ordering::Equal } else { Ordering::Greater },
+            (Item::Int(a), Some(Item::Int(b))) => cmp_digits(a, b),
+            (Item::Int(_), Some(_)) => Ordering::Greater,
+            (Item::Str(value), None) => comparable_qualifier(value).cmp(&comparable_qualifier("")),
+            (Item::Str(_), Some(Item::Int(_))) => Ordering::Less,
+            (Item::Str(a), Some(Item::Str(b))) => comparable_qualifier(a).cmp(&comparable_qualifier(b)),
+            (Item::Str(_), Some(Item::List(_))) => Ordering::Less,
+            (Item::List(items), None) => {
+                items.iter()
+                    .map(|item| item.compare_to(None))
+                    .find(|order| *order != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            },
+            (Item::List(_), Some(Item::Int(_))) => Ordering::Less,
+            (Item::List(_), Some(Item::Str(_))) => Ordering::Greater,
+            (Item::List(a), Some(Item::List(b))) => cmp_lists(a, b),
+        }
+    }
+}
+
+fn cmp_lists(a: &[Item], b: &[Item]) -> Ordering {
+    for index in 0..a.len().max(b.len()) {
+        let order: Ordering = match (a.get(index), b.get(index)) {
+            (None, None) => Ordering::Equal,
+            (None, Some(r)) => r.compare_to(None).reverse(),
+            (Some(l), r) => l.compare_to(r),
+        };
+        if order != Ordering::Equal {
+            return order
+        }
+    }
+    Ordering::Equal
+}
+
+impl PartialOrd for MavenVersion {
+    fn partial_cmp(&self, other: &MavenVersion) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MavenVersion {
+    fn cmp(&self, other: &MavenVersion) -> Ordering {
+        cmp_lists(&self.items, &other.items)
+    }
+}
+
+impl PartialEq for MavenVersion {
+    fn eq(&self, other: &MavenVersion) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Hash for MavenVersion {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Normalisation leaves a single tree for each equivalence class.
+        self.items.hash(state);
+    }
+}
+
+impl FromStr for MavenVersion {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<MavenVersion, ParseError> {
+        MavenVersion::parse(s)
+    }
+}
+
+/// Writes the canonical form, like `ComparableVersion.getCanonical()`.
+impl fmt::Display for MavenVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", Item::List(self.items.clone()))
+    }
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Item::Int(digits) => write!(f, "{}", digits),
+            Item::Str(value) => write!(f, "{}", value),
+            Item::List(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        match item {
+                            Item::List(_) => write!(f, "-")?,
+                            _ => write!(f, ".")?,
+                        }
+                    }
+                    write!(f, "{}", item)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
+    use super::*;
+
+    fn maven(s: &str) -> MavenVersion {
+        MavenVersion::parse(s).unwrap()
+    }
+
+    fn hash_of(version: &MavenVersion) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        version.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn assert_increasing(versions: &[&str]) {
+        for (i, lower) in versions.iter().enumerate() {
+            for higher in versions[i + 1..].iter() {
+                assert_eq!(maven(lower).cmp(&maven(higher)), Ordering::Less, "{} < {}", lower, higher);
+                assert_eq!(maven(higher).cmp(&maven(lower)), Ordering::Greater, "{} > {}", higher, lower);
+            }
+        }
+    }
+
+    fn assert_equal(a: &str, b: &str) {
+        assert_eq!(maven(a), maven(b), "{} == {}", a, b);
+        assert_eq!(hash_of(&maven(a)), hash_of(&maven(b)), "hash {} == {}", a, b);
+    }
+
+    // Sequences from Maven's ComparableVersionTest.
+    #[test]
+    fn test_versions_qualifier() {
+        assert_increasing(&[
+            "1-alpha2snapshot", "1-alpha2", "1-alpha-123", "1-beta-2", "1-beta123", "1-m2", "1-m11",
+            "1-rc", "1-cr2", "1-rc123", "1-SNAPSHOT", "1", "1-sp", "1-sp2", "1-sp123", "1-abc",
+            "1-def", "1-pom-1", "1-1-snapshot", "1-1", "1-2", "1-123",
+        ]);
+    }
+
+    #[test]
+    fn test_versions_number() {
+        assert_increasing(&[
+            "2.0", "2.0.a", "2-1", "2.0.2", "2.0.123", "2.1.0", "2.1-a", "2.1b", "2.1-c", "2.1-1",
+            "2.1.0.1", "2.2", "2.123", "11.a2", "11.a11", "11.b2", "11.b11", "11.m2", "11.m11",
+            "11", "11.a", "11b", "11c", "11m",
+        ]);
+    }
+
+    #[test]
+    fn test_versions_equal() {
+        let pairs = [
+            ("1", "1.0"), ("1", "1.0.0"), ("1.0", "1.0.0"), ("1", "1-0"), ("1", "1.0-0"), ("1.0", "1.0-0"),
+            ("1a", "1-a"), ("1a", "1.0-a"), ("1a", "1.0.0-a"), ("1.0a", "1-a"), ("1.0.0a", "1-a"),
+            ("1x", "1-x"), ("1x", "1.0-x"), ("1.0.0x", "1-x"),
+            ("1ga", "1"), ("1release", "1"), ("1final", "1"), ("1cr", "1rc"),
+            ("1a1", "1-alpha-1"), ("1b2", "1-beta-2"), ("1m3", "1-milestone-3"),
+            ("1X", "1x"), ("1A", "1a"), ("1GA", "1"), ("1RELEASE", "1"), ("1Cr", "1Rc"),
+            ("1m3", "1MileStone3"), ("1.0-RC1", "1.0-rc1"), ("2.0.0.Final", "2"),
+        ];
+        for (a, b) in pairs.iter() {
+            assert_equal(a, b);
+        }
+    }
+
+    #[test]
+    fn test_java_artifacts() {
+        assert_increasing(&["1.2.3-SNAPSHOT", "1.2.3", "1.2.3-sp1", "1.2.4"]);
+        assert_increasing(&["1.0-alpha1", "1.0-beta1", "1.0-M1", "1.0-RC1", "1.0", "1.0-sp1"]);
+    }
+
+    #[test]
+    fn test_canonical() {
+        assert_eq!(maven("1.0-alpha-1").to_string(), "1-alpha-1");
+        assert_eq!(maven("1a1").to_string(), "1-alpha-1");
+        assert_eq!(maven("2.0.0.Final").to_string(), "2");
+        assert_eq!(maven("1.2.3-SNAPSHOT").to_string(), "1.2.3-snapshot");
+    }
+
+    #[test]
+    fn test_invalid_versions() {
+        assert_eq!(MavenVersion::parse(""), Err(ParseError::EmptyComponent { offset: 0 }));
+        assert_eq!(MavenVersion::parse("1.0 beta"), Err(ParseError::UnexpectedCharacter { character: ' ', offset: 3 }));
+    }
+}
@@ -0,0 +1,235 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::compare::ParserConfig;
+use super::errors::ParseError;
+
+mod debian;
+mod maven;
+mod npm;
+mod pep440;
+mod permissive;
+mod rpm;
+mod rubygems;
+mod semver;
+
+pub use debian::{DebianScheme, DebianVersion};
+pub use maven::{Item, MavenScheme, MavenVersion};
+pub use npm::NpmScheme;
+pub use pep440::{LocalSegment, Pep440Scheme, Pep440Version, PreReleaseKind};
+pub use permissive::PermissiveScheme;
+pub use rpm::{RpmScheme, RpmVersion};
+pub use rubygems::{GemSegment, GemVersion, RubygemsScheme};
+pub use semver::{Identifier, SemVer, SemverScheme};
+pub(crate) use semver::{parse_pre_release_identifier, split_identifiers};
+
+/// Rules of one versioning ecosystem: how to read a version and how to order two of them.
+///
+/// Only `parse` is required: the other methods are derived from the parsed version
+/// `Ord` and `Display` implementations.
+pub trait VersionScheme {
+    type Version: Ord + fmt::Display;
+
+    fn parse(&self, raw_version: &str) -> Result<Self::Version, ParseError>;
+
+    /// Orders `raw_version_a` relative to `raw_version_b`.
+    fn compare(&self, raw_version_a: &str, raw_version_b: &str) -> Result<Ordering, ParseError> {
+        Ok(self.parse(raw_version_a)?.cmp(&self.parse(raw_version_b)?))
+    }
+
+    /// Like [`compare`](VersionScheme::compare), but `None` when either string is
+    /// not a version at all, like `latest` or a commit hash, instead of an error.
+    ///
+    /// Two identical non-versions are `Equal`. A malformed version, like `1.x.3`,
+    /// is still an error.
+    fn try_compare(&self, raw_version_a: &str, raw_version_b: &str) -> Result<Option<Ordering>, ParseError> {
+        let a_is_version: bool = is_version(raw_version_a, self.validate(raw_version_a))?;
+        let b_is_version: bool = is_version(raw_version_b, self.validate(raw_version_b))?;
+        if a_is_version && b_is_version {
+            return self.compare(raw_version_a, raw_version_b).map(Some)
+        }
+        match raw_version_a == raw_version_b {
+            true => Ok(Some(Ordering::Equal)),
+            false => Ok(None),
+        }
+    }
+
+    /// Checks that `raw_version` is valid in this scheme.
+    fn validate(&self, raw_version: &str) -> Result<(), ParseError> {
+        self.parse(raw_version).map(|_| ())
+    }
+
+    /// Writes `raw_version` in the canonical form of this scheme.
+    fn normalise(&self, raw_version: &str) -> Result<String, ParseError> {
+        Ok(self.parse(raw_version)?.to_string())
+    }
+}
+
+/// Whether `raw_version`, checked with `validation`, is a version: `false` for a
+/// name without digits, like `latest`, or a commit hash, like `3f2a9c1`.
+fn is_version(raw_version: &str, validation: Result<(), ParseError>) -> Result<bool, ParseError> {
+    let error: ParseError = match validation {
+        Ok(()) => return Ok(true),
+        Err(error) => error,
+    };
+    let is_name: bool = raw_version.starts_with(|c: char| c.is_alphabetic())
+        && !raw_version.contains(|c: char| c.is_ascii_digit())
+        && error.offset() == 0;
+    let is_commit_hash: bool = (7..=40).contains(&raw_version.len())
+        && raw_version.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+        && raw_version.contains(|c: char| c.is_ascii_alphabetic());
+    match is_name || is_commit_hash {
+        true => Ok(false),
+        false => Err(error),
+    }
+}
+
+/// Name of a [`VersionScheme`], as given to `--scheme`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    /// [`PermissiveScheme`]
+    Permissive,
+    /// [`SemverScheme`]
+    Semver,
+    /// [`DebianScheme`]
+    Debian,
+    /// [`Pep440Scheme`]
+    Pep440,
+    /// [`RpmScheme`]
+    Rpm,
+    /// [`MavenScheme`]
+    Maven,
+    /// [`RubygemsScheme`]
+    Rubygems,
+    /// [`NpmScheme`]
+    Npm,
+    /// [`SemverScheme`], with Cargo requirements for `satisfies`
+    Cargo,
+}
+
+/// Runs `$body` with `$s` bound to the `VersionScheme` named by `$scheme`.
+macro_rules! with_scheme {
+    ($scheme:expr, $parser_config:expr, |$s:ident| $body:expr) => {
+        match $scheme {
+            Scheme::Permissive => { let $s = PermissiveScheme::new($parser_config.clone()); $body },
+            Scheme::Semver => { let $s = SemverScheme; $body },
+            Scheme::Debian => { let $s = DebianScheme; $body },
+            Scheme::Pep440 => { let $s = Pep440Scheme; $body },
+            Scheme::Rpm => { let $s = RpmScheme; $body },
+            Scheme::Maven => { let $s = MavenScheme; $body },
+            Scheme::Rubygems => { let $s = RubygemsScheme; $body },
+            Scheme::Npm => { let $s = NpmScheme; $body },
+            Scheme::Cargo => { let $s = SemverScheme; $body },
+        }
+    };
+}
+
+impl Scheme {
+    /// Finds a scheme from its command line name.
+    pub fn from_name(name: &str) -> Option<Scheme> {
+        match name {
+            "permissive" => Some(Scheme::Permissive),
+            "semver" => Some(Scheme::Semver),
+            "debian" => Some(Scheme::Debian),
+            "pep440" => Some(Scheme::Pep440),
+            "rpm" => Some(Scheme::Rpm),
+            "maven" => Some(Scheme::Maven),
+            "rubygems" => Some(Scheme::Rubygems),
+            "npm" => Some(Scheme::Npm),
+            "cargo" => Some(Scheme::Cargo),
+            _ => None,
+        }
+    }
+
+    /// See [`VersionScheme::compare`].
+    ///
+    /// `parser_config` is only used by the permissive scheme.
+    pub fn compare(self, raw_version_a: &str, raw_version_b: &str, parser_config: &ParserConfig) -> Result<Ordering, ParseError> {
+        with_scheme!(self, parser_config, |scheme| scheme.compare(raw_version_a, raw_version_b))
+    }
+
+    /// See [`VersionScheme::try_compare`].
+    pub fn try_compare(self, raw_version_a: &str, raw_version_b: &str, parser_config: &ParserConfig) -> Result<Option<Ordering>, ParseError> {
+        with_scheme!(self, parser_config, |scheme| scheme.try_compare(raw_version_a, raw_version_b))
+    }
+
+    /// See [`VersionScheme::validate`].
+    pub fn validate(self, raw_version: &str, parser_config: &ParserConfig) -> Result<(), ParseError> {
+        with_scheme!(self, parser_config, |scheme| scheme.validate(raw_version))
+    }
+
+    /// See [`VersionScheme::normalise`].
+    pub fn normalise(self, raw_version: &str, parser_config: &ParserConfig) -> Result<String, ParseError> {
+        with_scheme!(self, parser_config, |scheme| scheme.normalise(raw_version))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::compare::permissive_parser_config;
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(Scheme::from_name("semver"), Some(Scheme::Semver));
+        assert_eq!(Scheme::from_name("debian"), Some(Scheme::Debian));
+        assert_eq!(Scheme::from_name("pep440"), Some(Scheme::Pep440));
+        assert_eq!(Scheme::from_name("rpm"), Some(Scheme::Rpm));
+        assert_eq!(Scheme::from_name("maven"), Some(Scheme::Maven));
+        assert_eq!(Scheme::from_name("rubygems"), Some(Scheme::Rubygems));
+        assert_eq!(Scheme::from_name("npm"), Some(Scheme::Npm));
+        assert_eq!(Scheme::from_name("cargo"), Some(Scheme::Cargo));
+        assert_eq!(Scheme::from_name("permissive"), Some(Scheme::Permissive));
+        assert_eq!(Scheme::from_name("unknown"), None);
+    }
+
+    #[test]
+    fn test_compare_depends_on_scheme() {
+        let parser_config: ParserConfig = permissive_parser_config();
+
+        assert_eq!(Scheme::Semver.compare("1.0.0+1", "1.0.0+2", &parser_config), Ok(Ordering::Equal));
+        assert_eq!(Scheme::Permissive.compare("1.0.0+1", "1.0.0+2", &parser_config), Ok(Ordering::Less));
+    }
+
+    #[test]
+    fn test_try_compare_incomparable() {
+        let parser_config: ParserConfig = permissive_parser_config();
+
+        assert_eq!(Scheme::Permissive.try_compare("latest", "1.2", &parser_config), Ok(None));
+        assert_eq!(Scheme::Permissive.try_compare("1.2", "3f2a9c1", &parser_config), Ok(None));
+        assert_eq!(Scheme::Semver.try_compare("3f2a9c1d", "9e8d7c6b", &parser_config), Ok(None));
+        assert_eq!(Scheme::Debian.try_compare("latest", "latest", &parser_config), Ok(Some(Ordering::Equal)));
+        assert_eq!(Scheme::Permissive.try_compare("1.2", "1.10", &parser_config), Ok(Some(Ordering::Less)));
+        assert_eq!(Scheme::Permissive.try_compare("1.x.3", "latest", &parser_config), Err(ParseError::InvalidLetter { letter: 'x', offset: 2 }));
+        assert!(Scheme::Permissive.try_compare("v1.2", "1.2", &parser_config).is_err());
+        assert!(Scheme::Permissive.try_compare("nightly", "1.2", &parser_config).unwrap().is_none());
+        // Maven reads any string as a version.
+        assert_eq!(Scheme::Maven.try_compare("latest", "1.2", &parser_config), Ok(Some(Ordering::Less)));
+    }
+
+    #[test]
+    fn test_validate_depends_on_scheme() {
+        let parser_config: ParserConfig = permissive_parser_config();
+
+        assert!(Scheme::Permissive.validate("1.0", &parser_config).is_ok());
+        assert!(Scheme::Semver.validate("1.0", &parser_config).is_err());
+        assert!(Scheme::Debian.validate("1.0~rc1-1", &parser_config).is_ok());
+    }
+
+    #[test]
+    fn test_normalise_depends_on_scheme() {
+        let parser_config: ParserConfig = permissive_parser_config();
+
+        assert_eq!(Scheme::Pep440.normalise("1.0-ALPHA-1", &parser_config), Ok("1.0a1".to_string()));
+        assert_eq!(Scheme::Permissive.normalise("1.0-RC1", &parser_config), Ok("1.0-rc1".to_string()));
+        assert_eq!(Scheme::Rpm.normalise("0:1.0-1", &parser_config), Ok("0:1.0-1".to_string()));
+    }
+
+    #[test]
+    fn test_rpm_scheme_skips_missing_release() {
+        let parser_config: ParserConfig = permissive_parser_config();
+
+        assert_eq!(Scheme::Rpm.compare("1.2", "1.2-3", &parser_config), Ok(Ordering::Equal));
+    }
+}
@@ -0,0 +1,33 @@
+use super::super::errors::ParseError;
+use super::semver::SemVer;
+use super::VersionScheme;
+
+/// Semantic versions as read by npm: like [`SemverScheme`](super::SemverScheme),
+/// with surrounding whitespace and a leading `v` allowed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NpmScheme;
+
+impl VersionScheme for NpmScheme {
+    type Version = SemVer;
+
+    fn parse(&self, raw_version: &str) -> Result<SemVer, ParseError> {
+        let offset: usize = raw_version.len() - raw_version.trim_start().len();
+        let trimmed: &str = raw_version.trim();
+        match trimmed.strip_prefix('v') {
+            Some(rest) => SemVer::parse(rest).map_err(|e| e.shifted(offset + 1)),
+            None => SemVer::parse(trimmed).map_err(|e| e.shifted(offset)),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leading_v_and_whitespace() {
+        assert_eq!(NpmScheme.parse(" v1.2.3 ").unwrap().to_string(), "1.2.3");
+        assert_eq!(NpmScheme.parse("v1.2"), Err(ParseError::EmptyComponent { offset: 4 }));
+    }
+}
@@ -0,0 +1,459 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use super::super::errors::ParseError;
+use super::VersionScheme;
+
+/// A Python version following [PEP 440](https://peps.python.org/pep-0440/).
+///
+/// Alternate spellings are normalised when parsing: `1.0-ALPHA-1` becomes
+/// `1.0a1`, `1.0-1` becomes `1.0.post1`, missing numbers are `0`.
+#[derive(Debug, Clone, Eq)]
+pub struct Pep440Version {
+    pub epoch: u64,
+    pub release: Vec<u64>,
+    pub pre: Option<(PreReleaseKind, u64)>,
+    pub post: Option<u64>,
+    pub dev: Option<u64>,
+    /// Local version label, empty when there is none.
+    pub local: Vec<LocalSegment>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PreReleaseKind {
+    /// `a`, spelled `a` or `alpha`
+    Alpha,
+    /// `b`, spelled `b` or `beta`
+    Beta,
+    /// `rc`, spelled `rc`, `c`, `pre` or `preview`
+    ReleaseCandidate,
+}
+
+/// One segment of a local version label. Alphanumeric segments sort before numeric ones.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LocalSegment {
+    AlphaNumeric(String),
+    Numeric(u64),
+}
+
+/// Python versions, as defined by PEP 440.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pep440Scheme;
+
+impl VersionScheme for Pep440Scheme {
+    type Version = Pep440Version;
+
+    fn parse(&self, raw_version: &str) -> Result<Pep440Version, ParseError> {
+        Pep440Version::parse(raw_version)
+    }
+}
+
+const PRE_RELEASE_LABELS: [(&str, PreReleaseKind); 8] = [
+    ("alpha", PreReleaseKind::Alpha),
+    ("a", PreReleaseKind::Alpha),
+    ("beta", PreReleaseKind::Beta),
+    ("b", PreReleaseKind::Beta),
+    ("preview", PreReleaseKind::ReleaseCandidate),
+    ("pre", PreReleaseKind::ReleaseCandidate),
+    ("rc", PreReleaseKind::ReleaseCandidate),
+    ("c", PreReleaseKind::ReleaseCandidate),
+];
+
+const POST_RELEASE_LABELS: [&str; 3] = ["post", "rev", "r"];
+
+/// Reads a lowercased version string from left to right.
+struct Cursor<'a> {
+    s: &'a str,
+    position: usize,
+    /// Offset of `s` in the raw version.
+    offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn rest(&self) -> &'a str {
+        &self.s[self.position..]
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        if self.rest().starts_with(prefix) {
+            self.position += prefix.len();
+            return true
+        }
+        false
+    }
+
+    fn eat_separator(&mut self) -> bool {
+        self.eat("-") || self.eat("_") || self.eat(".")
+    }
+
+    fn eat_number(&mut self) -> Result<Option<u64>, ParseError> {
+        let len: usize = self.rest().find(|c: char| !c.is_ascii_digit()).unwrap_or(self.rest().len());
+        if len == 0 {
+            return Ok(None)
+        }
+        let start: usize = self.position;
+        self.position += len;
+        self.s[start..self.position].parse()
+            .map(Some)
+            .map_err(|_| ParseError::NumericOverflow { offset: self.offset + start })
+    }
+
+    fn error(&self) -> ParseError {
+        match self.rest().chars().next() {
+            Some(character) => ParseError::UnexpectedCharacter { character, offset: self.offset + self.position },
+            None => ParseError::EmptyComponent { offset: self.offset + self.position },
+        }
+    }
+}
+
+impl Pep440Version {
+    pub fn parse(raw_version: &str) -> Result<Pep440Version, ParseError> {
+        let trimmed: &str = raw_version.trim();
+        let offset: usize = raw_version.len() - raw_version.trim_start().len();
+        if let Some((index, character)) = trimmed.char_indices().find(|(_, c)| !c.is_ascii()) {
+            return Err(ParseError::UnexpectedCharacter { character, offset: offset + index })
+        }
+        let lowercase: String = trimmed.to_ascii_lowercase();
+        let mut cursor = Cursor { s: &lowercase, position: 0, offset };
+
+        cursor.eat("v");
+        let mut epoch: u64 = 0;
+        let mut release: Vec<u64> = Vec::new();
+        match cursor.eat_number()? {
+            Some(number) => {
+                if cursor.eat("!") {
+                    epoch = number;
+                } else {
+                    release.push(number);
+                }
+            },
+            None => return Err(cursor.error()),
+        }
+        if release.is_empty() {
+            release.push(cursor.eat_number()?.ok_or_else(|| cursor.error())?);
+        }
+        loop {
+            let start: usize = cursor.position;
+            if cursor.eat(".") {
+                if let Some(number) = cursor.eat_number()? {
+                    release.push(number);
+                    continue
+                }
+            }
+            cursor.position = start;
+            break
+        }
+
+        let pre: Option<(PreReleaseKind, u64)> = parse_pre_release(&mut cursor)?;
+        let post: Option<u64> = parse_post_release(&mut cursor)?;
+        let dev: Option<u64> = parse_labelled_number(&mut cursor, &["dev"])?;
+        let local: Vec<LocalSegment> = if cursor.eat("+") {
+            parse_local(&mut cursor)?
+        } else {
+            Vec::new()
+        };
+        if !cursor.rest().is_empty() {
+            return Err(cursor.error())
+        }
+
+        Ok(Pep440Version { epoch, release, pre, post, dev, local })
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some() || self.dev.is_some()
+    }
+
+    fn cmp_release(&self, other: &Pep440Version) -> Ordering {
+        let len: usize = self.release.len().max(other.release.len());
+        for index in 0..len {
+            let order: Ordering = self.release.get(index).unwrap_or(&0).cmp(other.release.get(index).unwrap_or(&0));
+            if order != Ordering::Equal {
+                return order
+            }
+        }
+        Ordering::Equal
+    }
+
+    /// A development release without pre or post release sorts before every pre-release.
+    fn pre_key(&self) -> (u8, Option<(PreReleaseKind, u64)>) {
+        match (self.pre, self.post, self.dev) {
+            (None, None, Some(_)) => (0, None),
+            (Some(pre), _, _) => (1, Some(pre)),
+            (None, _, _) => (2, None),
+        }
+    }
+
+    fn cmp_dev(&self, other: &Pep440Version) -> Ordering {
+        match [self.dev, other.dev] {
+            [None, None] => Ordering::Equal,
+            [Some(_), None] => Ordering::Less,
+            [None, Some(_)] => Ordering::Greater,
+            [Some(x), Some(y)] => x.cmp(&y),
+        }
+    }
+
+    fn significant_release(&self) -> &[u64] {
+        let len: usize = self.release.iter().rposition(|n| *n != 0).map_or(0, |i| i + 1);
+        &self.release[..len]
+    }
+}
+
+fn parse_pre_release(cursor: &mut Cursor) -> Result<Option<(PreReleaseKind, u64)>, ParseError> {
+    let start: usize = cursor.position;
+    cursor.eat_separator();
+    for (label, kind) in PRE_RELEASE_LABELS.iter() {
+        if cursor.eat(label) {
+            cursor.eat_separator();
+            return Ok(Some((*kind, cursor.eat_number()?.unwrap_or(0))))
+        }
+    }
+    cursor.position = start;
+    Ok(None)
+}
+
+fn parse_post_release(cursor: &mut Cursor) -> Result<Option<u64>, ParseError> {
+    let start: usize = cursor.position;
+    if cursor.eat("-") {
+        if let Some(number) = cursor.eat_number()? {
+            return Ok(Some(number))
+        }
+        cursor.position = start;
+    }
+    parse_labelled_number(cursor, &POST_RELEASE_LABELS)
+}
+
+fn parse_labelled_number(cursor: &mut Cursor, labels: &[&str]) -> Result<Option<u64>, ParseError> {
+    let start: usize = cursor.position;
+    cursor.eat_separator();
+    for label in labels.iter() {
+        if cursor.eat(label) {
+            cursor.eat_separator();
+            return Ok(Some(cursor.eat_number()?.unwrap_or(0)))
+        }
+    }
+    cursor.position = start;
+    Ok(None)
+}
+
+fn parse_local(cursor: &mut Cursor) -> Result<Vec<LocalSegment>, ParseError> {
+    let mut segments: Vec<LocalSegment> = Vec::new();
+    loop {
+        let len: usize = cursor.rest().find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(cursor.rest().len());
+        if len == 0 {
+            return Err(cursor.error())
+        }
+        let start: usize = cursor.position;
+        let segment: &str = &cursor.s[start..start + len];
+        if segment.chars().all(|c| c.is_ascii_digit()) {
+            segments.push(LocalSegment::Numeric(cursor.eat_number()?.unwrap_or(0)));
+        } else {
+            segments.push(LocalSegment::AlphaNumeric(segment.to_string()));
+            cursor.position += len;
+        }
+        if !cursor.eat_separator() {
+            return Ok(segments)
+        }
+    }
+}
+
+impl PartialOrd for Pep440Version {
+    fn partial_cmp(&self, other: &Pep440Version) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pep440Version {
+    fn cmp(&self, other: &Pep440Version) -> Ordering {
+        let order: Ordering = self.epoch.cmp(&other.epoch);
+        if order != Ordering::Equal {
+            return order
+        }
+
+        let order: Ordering = self.cmp_release(other);
+        if order != Ordering::Equal {
+            return order
+        }
+
+        let order: Ordering = self.pre_key().cmp(&other.pre_key());
+        if order != Ordering::Equal {
+            return order
+        }
+
+        let order: Ordering = self.post.cmp(&other.post);
+        if order != Ordering::Equal {
+            return order
+        }
+
+        let order: Ordering = self.cmp_dev(other);
+        if order != Ordering::Equal {
+            return order
+        }
+
+        self.local.cmp(&other.local)
+    }
+}
+
+impl PartialEq for Pep440Version {
+    fn eq(&self, other: &Pep440Version) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Hash for Pep440Version {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.epoch.hash(state);
+        self.significant_release().hash(state);
+        self.pre.hash(state);
+        self.post.hash(state);
+        self.dev.hash(state);
+        self.local.hash(state);
+    }
+}
+
+impl FromStr for Pep440Version {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Pep440Version, ParseError> {
+        Pep440Version::parse(s)
+    }
+}
+
+impl fmt::Display for Pep440Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.epoch != 0 {
+            write!(f, "{}!", self.epoch)?;
+        }
+        let release: Vec<String> = self.release.iter().map(|n| n.to_string()).collect();
+        write!(f, "{}", release.join("."))?;
+        if let Some((kind, number)) = self.pre {
+            write!(f, "{}{}", kind, number)?;
+        }
+        if let Some(number) = self.post {
+            write!(f, ".post{}", number)?;
+        }
+        if let Some(number) = self.dev {
+            write!(f, ".dev{}", number)?;
+        }
+        if !self.local.is_empty() {
+            let local: Vec<String> = self.local.iter().map(|segment| segment.to_string()).collect();
+            write!(f, "+{}", local.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PreReleaseKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PreReleaseKind::Alpha => write!(f, "a"),
+            PreReleaseKind::Beta => write!(f, "b"),
+            PreReleaseKind::ReleaseCandidate => write!(f, "rc"),
+        }
+    }
+}
+
+impl fmt::Display for LocalSegment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LocalSegment::AlphaNumeric(s) => write!(f, "{}", s),
+            LocalSegment::Numeric(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pep440(s: &str) -> Pep440Version {
+        Pep440Version::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_pep_ordering_example() {
+        let ordered = [
+            "1.0.dev456", "1.0a1", "1.0a2.dev456", "1.0a12.dev456", "1.0a12",
+            "1.0b1.dev456", "1.0b2", "1.0b2.post345.dev456", "1.0b2.post345",
+            "1.0rc1.dev456", "1.0rc1", "1.0", "1.0+abc.5", "1.0+abc.7", "1.0+5",
+            "1.0.post456.dev34", "1.0.post456", "1.0.15", "1.1.dev1",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(pep440(pair[0]) < pep440(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn test_epoch() {
+        assert!(pep440("2013.10") < pep440("1!1.0"));
+        assert_eq!(pep440("0!1.0"), pep440("1.0"));
+    }
+
+    #[test]
+    fn test_implicit_zero_release_numbers() {
+        assert_eq!(pep440("1.0"), pep440("1.0.0"));
+        assert_eq!(pep440("1"), pep440("1.0.0.0"));
+    }
+
+    #[test]
+    fn test_normalisation() {
+        let cases = [
+            ("1.0RC1", "1.0rc1"),
+            ("1.0-alpha-1", "1.0a1"),
+            ("1.0.beta.2", "1.0b2"),
+            ("1.0c1", "1.0rc1"),
+            ("1.0pre1", "1.0rc1"),
+            ("1.0preview2", "1.0rc2"),
+            ("1.0a", "1.0a0"),
+            ("1.0-1", "1.0.post1"),
+            ("1.0.rev2", "1.0.post2"),
+            ("1.0r3", "1.0.post3"),
+            ("1.0_post", "1.0.post0"),
+            ("1.0.DEV", "1.0.dev0"),
+            ("1.0-dev3", "1.0.dev3"),
+            ("v1.0", "1.0"),
+            (" 1.0 ", "1.0"),
+            ("1!1.0", "1!1.0"),
+            ("1.0+ubuntu-1", "1.0+ubuntu.1"),
+            ("1.0+Ubuntu_01", "1.0+ubuntu.1"),
+            ("1.0a1.post1.dev2", "1.0a1.post1.dev2"),
+        ];
+        for (raw_version, normalised) in cases.iter() {
+            assert_eq!(pep440(raw_version).to_string(), *normalised, "{}", raw_version);
+        }
+    }
+
+    #[test]
+    fn test_alternate_spellings_are_equal() {
+        assert_eq!(pep440("1.0alpha1"), pep440("1.0a1"));
+        assert_eq!(pep440("1.0c1"), pep440("1.0rc1"));
+        assert_eq!(pep440("1.0-1"), pep440("1.0.post1"));
+    }
+
+    #[test]
+    fn test_local_segments() {
+        assert!(pep440("1.0+ubuntu.1") < pep440("1.0+ubuntu.2"));
+        assert!(pep440("1.0+ubuntu.1") < pep440("1.0+ubuntu.1.1"));
+        assert!(pep440("1.0+abc") < pep440("1.0+1"));
+        assert!(pep440("1.0") < pep440("1.0+ubuntu.1"));
+    }
+
+    #[test]
+    fn test_is_prerelease() {
+        assert!(pep440("1.0rc1").is_prerelease());
+        assert!(pep440("1.0.dev1").is_prerelease());
+        assert!(!pep440("1.0.post1").is_prerelease());
+    }
+
+    #[test]
+    fn test_invalid_versions() {
+        assert_eq!(Pep440Version::parse("foo"), Err(ParseError::UnexpectedCharacter { character: 'f', offset: 0 }));
+        assert_eq!(Pep440Version::parse("1.0foo"), Err(ParseError::UnexpectedCharacter { character: 'f', offset: 3 }));
+        assert_eq!(Pep440Version::parse("1.0+"), Err(ParseError::EmptyComponent { offset: 4 }));
+        assert_eq!(Pep440Version::parse("1.0+a..b"), Err(ParseError::UnexpectedCharacter { character: '.', offset: 6 }));
+        assert_eq!(Pep440Version::parse("1!"), Err(ParseError::EmptyComponent { offset: 2 }));
+        assert_eq!(Pep440Version::parse(""), Err(ParseError::EmptyComponent { offset: 0 }));
+    }
+}
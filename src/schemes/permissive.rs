@@ -0,0 +1,55 @@
+use super::super::compare::{parse_raw_version, ParserConfig, Version};
+use super::super::errors::ParseError;
+use super::VersionScheme;
+
+/// The `ParserConfig`-driven parser, accepting most version shapes.
+#[derive(Debug, Clone)]
+pub struct PermissiveScheme {
+    pub parser_config: ParserConfig,
+}
+
+impl PermissiveScheme {
+    pub fn new(parser_config: ParserConfig) -> PermissiveScheme {
+        PermissiveScheme { parser_config }
+    }
+}
+
+impl VersionScheme for PermissiveScheme {
+    type Version = Version;
+
+    fn parse(&self, raw_version: &str) -> Result<Version, ParseError> {
+        parse_raw_version(raw_version, &self.parser_config)
+    }
+
+    /// Writes the version back the canonical way, with the configured epoch delimiter.
+    fn normalise(&self, raw_version: &str) -> Result<String, ParseError> {
+        Ok(self.parse(raw_version)?.to_normalised_string_with(&self.parser_config))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::*;
+    use super::super::super::compare::permissive_parser_config;
+
+    #[test]
+    fn test_compare_with_parser_config() {
+        let mut parser_config: ParserConfig = permissive_parser_config();
+        parser_config.epoch_delimiter = Some('!');
+        let scheme = PermissiveScheme::new(parser_config);
+
+        assert_eq!(scheme.compare("1!1.0", "2.0"), Ok(Ordering::Greater));
+    }
+
+    #[test]
+    fn test_normalise_keeps_epoch_delimiter() {
+        let mut parser_config: ParserConfig = permissive_parser_config();
+        parser_config.epoch_delimiter = Some('!');
+        let scheme = PermissiveScheme::new(parser_config);
+
+        assert_eq!(scheme.normalise("1!1.0-RC1"), Ok("1!1.0-rc1".to_string()));
+    }
+}
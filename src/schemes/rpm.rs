@@ -0,0 +1,439 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use super::super::errors::ParseError;
+use super::VersionScheme;
+
+/// An RPM `[epoch:]version[-release]` label, ordered like `rpmvercmp`.
+///
+/// `Ord` is a total order where a missing release sorts before any release.
+/// rpm itself skips the release when one side has none: use
+/// [`RpmVersion::compare_evr`] to get exactly its answer.
+#[derive(Debug, Clone, Eq)]
+pub struct RpmVersion {
+    pub epoch: Option<u64>,
+    pub version: String,
+    pub release: Option<String>,
+}
+
+/// RPM `epoch:version-release` labels, ordered like `rpmvercmp`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RpmScheme;
+
+impl VersionScheme for RpmScheme {
+    type Version = RpmVersion;
+
+    fn parse(&self, raw_version: &str) -> Result<RpmVersion, ParseError> {
+        RpmVersion::parse(raw_version)
+    }
+
+    /// Compares like rpm, see [`RpmVersion::compare_evr`].
+    ///
+    /// As in rpm, the release is skipped when one side has none, so this is
+    /// not transitive: `1.2-1` and `1.2-2` both equal `1.2` but differ from
+    /// each other. Sort [`RpmVersion`] values, whose `Ord` is total, instead.
+    fn compare(&self, raw_version_a: &str, raw_version_b: &str) -> Result<Ordering, ParseError> {
+        Ok(self.parse(raw_version_a)?.compare_evr(&self.parse(raw_version_b)?))
+    }
+}
+
+impl RpmVersion {
+    pub fn parse(raw_version: &str) -> Result<RpmVersion, ParseError> {
+        let (epoch, version_offset): (Option<u64>, usize) = match raw_version.find(':') {
+            Some(index) => (Some(parse_epoch(&raw_version[..index])?), index + 1),
+            None => (None, 0),
+        };
+        let tail: &str = &raw_version[version_offset..];
+        let (version, release): (&str, Option<&str>) = match tail.rfind('-') {
+            Some(index) => (&tail[..index], Some(&tail[index + 1..])),
+            None => (tail, None),
+        };
+
+        if version.is_empty() {
+            return Err(ParseError::EmptyComponent { offset: version_offset })
+        }
+        check_characters(version, version_offset, "._+~^-")?;
+        if let Some(release) = release {
+            let release_offset: usize = version_offset + version.len() + 1;
+            if release.is_empty() {
+                return Err(ParseError::EmptyComponent { offset: release_offset })
+            }
+            check_characters(release, release_offset, "._+~^")?;
+        }
+
+        Ok(RpmVersion {
+            epoch,
+            version: version.to_string(),
+            release: release.map(|s| s.to_string()),
+        })
+    }
+
+    /// Compares like rpm's `rpmverCmp`: a missing epoch is `0` and the
+    /// releases are compared only when both labels have one.
+    ///
+    /// This is not a total order, see [`RpmScheme::compare`].
+    pub fn compare_evr(&self, other: &RpmVersion) -> Ordering {
+        let order: Ordering = self.epoch.unwrap_or(0).cmp(&other.epoch.unwrap_or(0));
+        if order != Ordering::Equal {
+            return order
+        }
+
+        let order: Ordering = rpmvercmp(&self.version, &other.version);
+        if order != Ordering::Equal {
+            return order
+        }
+
+        match (&self.release, &other.release) {
+            (Some(x), Some(y)) => rpmvercmp(x, y),
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+fn parse_epoch(raw_epoch: &str) -> Result<u64, ParseError> {
+    if raw_epoch.is_empty() {
+        return Err(ParseError::EmptyComponent { offset: 0 })
+    }
+    if let Some((index, character)) = raw_epoch.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        return Err(ParseError::UnexpectedCharacter { character, offset: index })
+    }
+    raw_epoch.parse().map_err(|_| ParseError::NumericOverflow { offset: 0 })
+}
+
+fn check_characters(s: &str, offset: usize, allowed_symbols: &str) -> Result<(), ParseError> {
+    match s.char_indices().find(|(_, c)| !(c.is_ascii_alphanumeric() || allowed_symbols.contains(*c))) {
+        Some((index, character)) => Err(ParseError::UnexpectedCharacter { character, offset: offset + index }),
+        None => Ok(()),
+    }
+}
+
+fn is_separator(c: u8) -> bool {
+    !c.is_ascii_alphanumeric() && c != b'~' && c != b'^'
+}
+
+/// rpm's `rpmvercmp`: compares alphabetic and numeric segments, ignoring
+/// other separators. `~` sorts before anything, even the end of the string,
+/// and `^` sorts after the end of the string but before anything else.
+fn rpmvercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal
+    }
+    let a: &[u8] = a.as_bytes();
+    let b: &[u8] = b.as_bytes();
+    let (mut one, mut two): (usize, usize) = (0, 0);
+
+    while one < a.len() || two < b.len() {
+        while one < a.len() && is_separator(a[one]) {
+            one += 1;
+        }
+        while two < b.len() && is_separator(b[two]) {
+            two += 1;
+        }
+        let (c1, c2): (Option<u8>, Option<u8>) = (a.get(one).cloned(), b.get(two).cloned());
+
+        if c1 == Some(b'~') || c2 == Some(b'~') {
+            if c1 != Some(b'~') {
+                return Ordering::Greater
+            }
+            if c2 != Some(b'~') {
+                return Ordering::Less
+            }
+            one += 1;
+            two += 1;
+            continue
+        }
+
+        if c1 == Some(b'^') || c2 == Some(b'^') {
+            if c1.is_none() {
+                return Ordering::Less
+            }
+            if c2.is_none() {
+                return Ordering::Greater
+            }
+            if c1 != Some(b'^') {
+                return Ordering::Greater
+            }
+            if c2 != Some(b'^') {
+                return Ordering::Less
+            }
+            one += 1;
+            two += 1;
+            continue
+        }
+
+        if c1.is_none() || c2.is_none() {
+            break
+        }
+
+        let is_numeric: bool = a[one].is_ascii_digit();
+        let in_segment = |c: &u8| if is_numeric { c.is_ascii_digit() } else { c.is_ascii_alphabetic() };
+        let end1: usize = one + a[one..].iter().take_while(|c| in_segment(c)).count();
+        let end2: usize = two + b[two..].iter().take_while(|c| in_segment(c)).count();
+
+        if end2 == two {
+            // Numeric segments are always newer than alphabetic ones.
+            return if is_numeric { Ordering::Greater } else { Ordering::Less }
+        }
+
+        let mut segment1: &[u8] = &a[one..end1];
+        let mut segment2: &[u8] = &b[two..end2];
+        if is_numeric {
+            while segment1.first() == Some(&b'0') {
+                segment1 = &segment1[1..];
+            }
+            while segment2.first() == Some(&b'0') {
+                segment2 = &segment2[1..];
+            }
+            let order: Ordering = segment1.len().cmp(&segment2.len());
+            if order != Ordering::Equal {
+                return order
+            }
+        }
+        let order: Ordering = segment1.cmp(segment2);
+        if order != Ordering::Equal {
+            return order
+        }
+        one = end1;
+        two = end2;
+    }
+
+    match (one >= a.len(), two >= b.len()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        _ => Ordering::Greater,
+    }
+}
+
+/// Segments and `~`/`^` markers of `s`, without separators and leading zeros.
+///
+/// Two strings are equal for `rpmvercmp` exactly when their tokens are equal.
+fn tokens(s: &str) -> Vec<&str> {
+    let mut tokens: Vec<&str> = Vec::new();
+    let mut rest: &str = s;
+    while let Some(c) = rest.chars().next() {
+        let len: usize = if c == '~' || c == '^' {
+            1
+        } else if c.is_ascii_digit() {
+            rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len())
+        } else if c.is_ascii_alphabetic() {
+            rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len())
+        } else {
+            rest = &rest[c.len_utf8()..];
+            continue
+        };
+        let token: &str = &rest[..len];
+        if token.starts_with(|c: char| c.is_ascii_digit()) {
+            tokens.push(token.trim_start_matches('0'));
+        } else {
+            tokens.push(token);
+        }
+        rest = &rest[len..];
+    }
+    tokens
+}
+
+impl PartialOrd for RpmVersion {
+    fn partial_cmp(&self, other: &RpmVersion) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RpmVersion {
+    fn cmp(&self, other: &RpmVersion) -> Ordering {
+        let order: Ordering = self.epoch.unwrap_or(0).cmp(&other.epoch.unwrap_or(0));
+        if order != Ordering::Equal {
+            return order
+        }
+
+        let order: Ordering = rpmvercmp(&self.version, &other.version);
+        if order != Ordering::Equal {
+            return order
+        }
+
+        let release = |version: &RpmVersion| version.release.clone().unwrap_or_default();
+        rpmvercmp(&release(self), &release(other))
+    }
+}
+
+impl PartialEq for RpmVersion {
+    fn eq(&self, other: &RpmVersion) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Hash for RpmVersion {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.epoch.unwrap_or(0).hash(state);
+        tokens(&self.version).hash(state);
+        tokens(self.release.as_deref().unwrap_or("")).hash(state);
+    }
+}
+
+impl FromStr for RpmVersion {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<RpmVersion, ParseError> {
+        RpmVersion::parse(s)
+    }
+}
+
+impl fmt::Display for RpmVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(epoch) = self.epoch {
+            write!(f, "{}:", epoch)?;
+        }
+        write!(f, "{}", self.version)?;
+        if let Some(release) = &self.release {
+            write!(f, "-{}", release)?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
+    use super::*;
+
+    fn rpm(s: &str) -> RpmVersion {
+        RpmVersion::parse(s).unwrap()
+    }
+
+    fn hash_of(version: &RpmVersion) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        version.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // Cases from rpm's own rpmvercmp test suite.
+    const RPMVERCMP: [(&str, &str, Ordering); 60] = [
+        ("1.0", "1.0", Ordering::Equal),
+        ("1.0", "2.0", Ordering::Less),
+        ("2.0", "1.0", Ordering::Greater),
+        ("2.0.1", "2.0.1", Ordering::Equal),
+        ("2.0", "2.0.1", Ordering::Less),
+        ("2.0.1a", "2.0.1a", Ordering::Equal),
+        ("2.0.1a", "2.0.1", Ordering::Greater),
+        ("5.5p1", "5.5p1", Ordering::Equal),
+        ("5.5p1", "5.5p2", Ordering::Less),
+        ("5.5p10", "5.5p1", Ordering::Greater),
+        ("10xyz", "10.1xyz", Ordering::Less),
+        ("xyz10", "xyz10", Ordering::Equal),
+        ("xyz10", "xyz10.1", Ordering::Less),
+        ("xyz.4", "xyz.4", Ordering::Equal),
+        ("xyz.4", "8", Ordering::Less),
+        ("8", "xyz.4", Ordering::Greater),
+        ("xyz.4", "2", Ordering::Less),
+        ("5.5p2", "5.6p1", Ordering::Less),
+        ("5.6p1", "6.5p1", Ordering::Less),
+        ("6.0.rc1", "6.0", Ordering::Greater),
+        ("10b2", "10a1", Ordering::Greater),
+        ("10a2", "10b2", Ordering::Less),
+        ("1.0aa", "1.0aa", Ordering::Equal),
+        ("1.0a", "1.0aa", Ordering::Less),
+        ("10.0001", "10.0001", Ordering::Equal),
+        ("10.0001", "10.1", Ordering::Equal),
+        ("10.0039", "10.39", Ordering::Equal),
+        ("4.999.9", "5.0", Ordering::Less),
+        ("20101121", "20101121", Ordering::Equal),
+        ("20101121", "20101122", Ordering::Less),
+        ("2_0", "2_0", Ordering::Equal),
+        ("2.0", "2_0", Ordering::Equal),
+        ("a", "a", Ordering::Equal),
+        ("a+", "a+", Ordering::Equal),
+        ("a+", "a_", Ordering::Equal),
+        ("+a", "+a", Ordering::Equal),
+        ("+a", "_a", Ordering::Equal),
+        ("+_", "+_", Ordering::Equal),
+        ("_+", "+_", Ordering::Equal),
+        ("_+", "_", Ordering::Equal),
+        ("+", "_", Ordering::Equal),
+        ("1.0~rc1", "1.0~rc1", Ordering::Equal),
+        ("1.0~rc1", "1.0", Ordering::Less),
+        ("1.0", "1.0~rc1", Ordering::Greater),
+        ("1.0~rc1", "1.0~rc2", Ordering::Less),
+        ("1.0~rc1~git123", "1.0~rc1", Ordering::Less),
+        ("1.0^", "1.0^", Ordering::Equal),
+        ("1.0^", "1.0", Ordering::Greater),
+        ("1.0", "1.0^", Ordering::Less),
+        ("1.0^git1", "1.0^git1", Ordering::Equal),
+        ("1.0^git1", "1.0", Ordering::Greater),
+        ("1.0^git1", "1.0^git2", Ordering::Less),
+        ("1.0^git1", "1.01", Ordering::Less),
+        ("1.0^20160101", "1.0^20160101", Ordering::Equal),
+        ("1.0^20160101", "1.0.1", Ordering::Less),
+        ("1.0^20160102", "1.0^20160101^git1", Ordering::Greater),
+        ("1.0~rc1^git1", "1.0~rc1", Ordering::Greater),
+        ("1.0^git1~pre", "1.0^git1", Ordering::Less),
+        ("1.0^git1", "1.0^git1~pre", Ordering::Greater),
+        ("1.0~rc1^git1", "1.0", Ordering::Less),
+    ];
+
+    #[test]
+    fn test_rpmvercmp_suite() {
+        for (a, b, expected) in RPMVERCMP.iter() {
+            assert_eq!(rpmvercmp(a, b), *expected, "rpmvercmp({}, {})", a, b);
+            assert_eq!(rpmvercmp(b, a), expected.reverse(), "rpmvercmp({}, {})", b, a);
+        }
+    }
+
+    #[test]
+    fn test_equal_tokens_for_equal_versions() {
+        for (a, b, expected) in RPMVERCMP.iter() {
+            assert_eq!(tokens(a) == tokens(b), *expected == Ordering::Equal, "tokens({}) == tokens({})", a, b);
+        }
+    }
+
+    #[test]
+    fn test_dist_tags() {
+        assert!(rpm("1.2-3.el8") < rpm("1.2-3.el9"));
+        assert!(rpm("1.2-3.fc38") < rpm("1.2-4.fc37"));
+        assert_eq!(rpm("1.2-3.el8").compare_evr(&rpm("1.2-3.el9")), Ordering::Less);
+    }
+
+    #[test]
+    fn test_epoch() {
+        let version: RpmVersion = rpm("2:1.0-1");
+
+        assert_eq!(version.epoch, Some(2));
+        assert!(rpm("1:1.0-1") > rpm("2.0-1"));
+        assert_eq!(rpm("0:1.0-1"), rpm("1.0-1"));
+        assert_eq!(hash_of(&rpm("0:1.0-1")), hash_of(&rpm("1.0-1")));
+    }
+
+    #[test]
+    fn test_missing_release() {
+        assert_eq!(rpm("1.2").compare_evr(&rpm("1.2-3")), Ordering::Equal);
+        assert!(rpm("1.2") < rpm("1.2-3"));
+    }
+
+    #[test]
+    fn test_compare_evr_is_not_transitive() {
+        assert_eq!(RpmScheme.compare("1.2-1", "1.2"), Ok(Ordering::Equal));
+        assert_eq!(RpmScheme.compare("1.2", "1.2-2"), Ok(Ordering::Equal));
+        assert_eq!(RpmScheme.compare("1.2-1", "1.2-2"), Ok(Ordering::Less));
+
+        let mut versions: Vec<RpmVersion> = vec![rpm("1.2-2"), rpm("1.2"), rpm("1.2-1")];
+        versions.sort();
+        assert_eq!(versions, vec![rpm("1.2"), rpm("1.2-1"), rpm("1.2-2")]);
+    }
+
+    #[test]
+    fn test_display() {
+        for raw_version in ["1.0", "1:1.0~rc1-1.el9", "1.0^git1-2.fc38"].iter() {
+            assert_eq!(rpm(raw_version).to_string(), *raw_version);
+        }
+    }
+
+    #[test]
+    fn test_invalid_versions() {
+        assert_eq!(RpmVersion::parse("a:1.0"), Err(ParseError::UnexpectedCharacter { character: 'a', offset: 0 }));
+        assert_eq!(RpmVersion::parse("1.0-"), Err(ParseError::EmptyComponent { offset: 4 }));
+        assert_eq!(RpmVersion::parse("-1"), Err(ParseError::EmptyComponent { offset: 0 }));
+        assert_eq!(RpmVersion::parse("1.0-1/2"), Err(ParseError::UnexpectedCharacter { character: '/', offset: 5 }));
+    }
+}
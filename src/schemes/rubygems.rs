@@ -0,0 +1,332 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use super::super::errors::ParseError;
+use super::VersionScheme;
+
+/// A RubyGems version, ordered like `Gem::Version#<=>`.
+///
+/// As in RubyGems, `-` is read as `.pre.` and any segment with a letter makes
+/// the version a pre-release: `1.0.0.pre.2 < 1.0.0`.
+#[derive(Debug, Clone, Eq)]
+pub struct GemVersion {
+    pub version: String,
+    pub segments: Vec<GemSegment>,
+}
+
+/// A run of digits or a run of letters of a gem version.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GemSegment {
+    Number(u64),
+    String(String),
+}
+
+/// RubyGems `Gem::Version` ordering.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RubygemsScheme;
+
+impl VersionScheme for RubygemsScheme {
+    type Version = GemVersion;
+
+    fn parse(&self, raw_version: &str) -> Result<GemVersion, ParseError> {
+        GemVersion::parse(raw_version)
+    }
+}
+
+impl GemVersion {
+    /// Parses like `Gem::Version.new`: surrounding whitespace is ignored and
+    /// a blank version is `0`.
+    pub fn parse(raw_version: &str) -> Result<GemVersion, ParseError> {
+        let offset: usize = raw_version.len() - raw_version.trim_start().len();
+        let stripped: &str = raw_version.trim();
+        if stripped.is_empty() {
+            return GemVersion::parse("0")
+        }
+        check_syntax(stripped, offset)?;
+
+        let version: String = stripped.replace('-', ".pre.");
+        let segments: Vec<GemSegment> = scan_segments(&version)?;
+        Ok(GemVersion { version, segments })
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        self.segments.iter().any(|segment| matches!(segment, GemSegment::String(_)))
+    }
+
+    /// The version without its pre-release segments, like `Gem::Version#release`.
+    pub fn release(&self) -> GemVersion {
+        if !self.is_prerelease() {
+            return self.clone()
+        }
+        let numbers: Vec<GemSegment> = self.segments.iter()
+            .take_while(|segment| matches!(segment, GemSegment::Number(_)))
+            .cloned()
+            .collect();
+        GemVersion::from_segments(numbers)
+    }
+
+    /// The next release of the last significant segment, like `Gem::Version#bump`:
+    /// `1.2.3` bumps to `1.3` and `5.3.1.b.2` to `5.4`.
+    pub fn bump(&self) -> GemVersion {
+        let mut numbers: Vec<u64> = self.segments.iter()
+            .take_while(|segment| matches!(segment, GemSegment::Number(_)))
+            .map(|segment| match segment {
+                GemSegment::Number(n) => *n,
+                GemSegment::String(_) => 0,
+            })
+            .collect();
+        if numbers.len() > 1 {
+            numbers.pop();
+        }
+        if let Some(last) = numbers.last_mut() {
+            *last = last.saturating_add(1);
+        }
+        GemVersion::from_segments(numbers.into_iter().map(GemSegment::Number).collect())
+    }
+
+    /// Whether `self` matches the pessimistic constraint `~> requirement`:
+    /// `self >= requirement` and the release of `self` is below `requirement.bump()`.
+    pub fn matches_pessimistic(&self, requirement: &GemVersion) -> bool {
+        self >= requirement && self.release() < requirement.bump()
+    }
+
+    /// Segments with trailing zeros of the release and of the pre-release parts dropped.
+    fn canonical_segments(&self) -> Vec<GemSegment> {
+        let string_start: usize = self.segments.iter()
+            .position(|segment| matches!(segment, GemSegment::String(_)))
+            .unwrap_or(self.segments.len());
+        let (numbers, strings) = self.segments.split_at(string_start);
+        let mut canonical: Vec<GemSegment> = drop_trailing_zeros(numbers);
+        canonical.extend(drop_trailing_zeros(strings));
+        canonical
+    }
+
+    fn from_segments(segments: Vec<GemSegment>) -> GemVersion {
+        let raw_segments: Vec<String> = segments.iter().map(|segment| segment.to_string()).collect();
+        GemVersion { version: raw_segments.join("."), segments }
+    }
+}
+
+fn drop_trailing_zeros(segments: &[GemSegment]) -> Vec<GemSegment> {
+    let length: usize = segments.iter()
+        .rposition(|segment| *segment != GemSegment::Number(0))
+        .map_or(0, |index| index + 1);
+    segments[..length].to_vec()
+}
+
+/// Checks the `Gem::Version::ANCHORED_VERSION_PATTERN` syntax:
+/// `[0-9]+(\.[0-9a-zA-Z]+)*(-[0-9A-Za-z-]+(\.[0-9A-Za-z-]+)*)?`.
+fn check_syntax(s: &str, offset: usize) -> Result<(), ParseError> {
+    let (release, pre_release): (&str, Option<&str>) = match s.find('-') {
+        Some(index) => (&s[..index], Some(&s[index + 1..])),
+        None => (s, None),
+    };
+
+    let mut part_offset: usize = offset;
+    for (index, part) in release.split('.').enumerate() {
+        if part.is_empty() {
+            return Err(ParseError::EmptyComponent { offset: part_offset })
+        }
+        let allowed = |c: char| if index == 0 { c.is_ascii_digit() } else { c.is_ascii_alphanumeric() };
+        if let Some((position, character)) = part.char_indices().find(|(_, c)| !allowed(*c)) {
+            return Err(ParseError::UnexpectedCharacter { character, offset: part_offset + position })
+        }
+        part_offset += part.len() + 1;
+    }
+    if let Some(pre_release) = pre_release {
+        for part in pre_release.split('.') {
+            if part.is_empty() {
+                return Err(ParseError::EmptyComponent { offset: part_offset })
+            }
+            if let Some((position, character)) = part.char_indices().find(|(_, c)| !(c.is_ascii_alphanumeric() || *c == '-')) {
+                return Err(ParseError::UnexpectedCharacter { character, offset: part_offset + position })
+            }
+            part_offset += part.len() + 1;
+        }
+    }
+    Ok(())
+}
+
+/// Splits into runs of digits and runs of letters, like `scan(/[0-9]+|[a-z]+/i)`.
+fn scan_segments(version: &str) -> Result<Vec<GemSegment>, ParseError> {
+    let mut segments: Vec<GemSegment> = Vec::new();
+    let bytes: &[u8] = version.as_bytes();
+    let mut start: usize = 0;
+    while start < bytes.len() {
+        if !bytes[start].is_ascii_alphanumeric() {
+            start += 1;
+            continue
+        }
+        let is_digit: bool = bytes[start].is_ascii_digit();
+        let length: usize = bytes[start..].iter()
+            .take_while(|b| if is_digit { b.is_ascii_digit() } else { b.is_ascii_alphabetic() })
+            .count();
+        let run: &str = &version[start..start + length];
+        segments.push(match is_digit {
+            true => GemSegment::Number(run.parse().map_err(|_| ParseError::NumericOverflow { offset: start })?),
+            false => GemSegment::String(run.to_string()),
+        });
+        start += length;
+    }
+    Ok(segments)
+}
+
+impl PartialOrd for GemVersion {
+    fn partial_cmp(&self, other: &GemVersion) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GemVersion {
+    fn cmp(&self, other: &GemVersion) -> Ordering {
+        let lhsegments: Vec<GemSegment> = self.canonical_segments();
+        let rhsegments: Vec<GemSegment> = other.canonical_segments();
+        let zero: GemSegment = GemSegment::Number(0);
+        for index in 0..lhsegments.len().max(rhsegments.len()) {
+            let lhs: &GemSegment = lhsegments.get(index).unwrap_or(&zero);
+            let rhs: &GemSegment = rhsegments.get(index).unwrap_or(&zero);
+            let order: Ordering = match (lhs, rhs) {
+                (GemSegment::Number(x), GemSegment::Number(y)) => x.cmp(y),
+                (GemSegment::String(_), GemSegment::Number(_)) => Ordering::Less,
+                (GemSegment::Number(_), GemSegment::String(_)) => Ordering::Greater,
+                (GemSegment::String(x), GemSegment::String(y)) => x.cmp(y),
+            };
+            if order != Ordering::Equal {
+                return order
+            }
+        }
+        Ordering::Equal
+    }
+}
+
+impl PartialEq for GemVersion {
+    fn eq(&self, other: &GemVersion) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Hash for GemVersion {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical_segments().hash(state);
+    }
+}
+
+impl FromStr for GemVersion {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<GemVersion, ParseError> {
+        GemVersion::parse(s)
+    }
+}
+
+/// Writes the version like `Gem::Version#to_s`, with `-` spelled `.pre.`.
+impl fmt::Display for GemVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.version)
+    }
+}
+
+impl fmt::Display for GemSegment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GemSegment::Number(n) => write!(f, "{}", n),
+            GemSegment::String(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
+    use super::*;
+
+    fn gem(s: &str) -> GemVersion {
+        GemVersion::parse(s).unwrap()
+    }
+
+    fn hash_of(version: &GemVersion) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        version.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // Cases from RubyGems' test_gem_version.rb.
+    #[test]
+    fn test_spaceship() {
+        let cases = [
+            ("1.0", "1.0.a", Ordering::Greater),
+            ("1.8.2", "0.0.0", Ordering::Greater),
+            ("1.8.2", "1.8.2.a", Ordering::Greater),
+            ("1.8.2.b", "1.8.2.a", Ordering::Greater),
+            ("1.8.2.a", "1.8.2", Ordering::Less),
+            ("1.8.2.a10", "1.8.2.a9", Ordering::Greater),
+            ("", "0", Ordering::Equal),
+            ("0.beta.1", "0.0.beta.1", Ordering::Equal),
+            ("0.0.beta", "0.0.beta.1", Ordering::Less),
+            ("0.0.beta", "0.beta.1", Ordering::Less),
+            ("5.a", "5.0.0.rc2", Ordering::Less),
+            ("5.x", "5.0.0.rc2", Ordering::Greater),
+            ("1.9.3", "1.9.3.0", Ordering::Equal),
+            ("1.0.0-1", "1.0.0.pre.1", Ordering::Equal),
+            ("1.0.0.pre.2", "1.0.0", Ordering::Less),
+        ];
+        for (a, b, expected) in cases.iter() {
+            assert_eq!(gem(a).cmp(&gem(b)), *expected, "{} <=> {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_equal_versions_hash_alike() {
+        for (a, b) in [("1.9.3", "1.9.3.0"), ("0.beta.1", "0.0.beta.1"), ("1.0.a.0", "1.0.a"), ("1-1", "1.pre.1")].iter() {
+            assert_eq!(gem(a), gem(b));
+            assert_eq!(hash_of(&gem(a)), hash_of(&gem(b)), "{} {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_prerelease() {
+        assert!(gem("1.2.0.a").is_prerelease());
+        assert!(gem("1.0.0-1").is_prerelease());
+        assert!(gem("2.9.b").is_prerelease());
+        assert!(!gem("1.2.0").is_prerelease());
+        assert_eq!(gem("1.2.0.a").release().to_string(), "1.2.0");
+    }
+
+    #[test]
+    fn test_bump() {
+        assert_eq!(gem("5.2.4").bump().to_string(), "5.3");
+        assert_eq!(gem("5.2.4.a").bump().to_string(), "5.3");
+        assert_eq!(gem("5.2.4.a10").bump().to_string(), "5.3");
+        assert_eq!(gem("5").bump().to_string(), "6");
+    }
+
+    #[test]
+    fn test_pessimistic() {
+        assert!(gem("2.2").matches_pessimistic(&gem("2.2")));
+        assert!(gem("2.9").matches_pessimistic(&gem("2.2")));
+        assert!(!gem("3.0").matches_pessimistic(&gem("2.2")));
+        assert!(gem("2.2.9").matches_pessimistic(&gem("2.2.0")));
+        assert!(!gem("2.3").matches_pessimistic(&gem("2.2.0")));
+        assert!(!gem("2.2.0.pre").matches_pessimistic(&gem("2.2.0")));
+        assert!(gem("2.3.pre").matches_pessimistic(&gem("2.2")));
+    }
+
+    #[test]
+    fn test_display_like_to_s() {
+        assert_eq!(gem(" 1.0.0-1 ").to_string(), "1.0.0.pre.1");
+        assert_eq!(gem("1.0.a").to_string(), "1.0.a");
+    }
+
+    #[test]
+    fn test_invalid_versions() {
+        assert_eq!(GemVersion::parse("junk"), Err(ParseError::UnexpectedCharacter { character: 'j', offset: 0 }));
+        assert_eq!(GemVersion::parse("1.0\n2.0"), Err(ParseError::UnexpectedCharacter { character: '\n', offset: 3 }));
+        assert_eq!(GemVersion::parse("1..2"), Err(ParseError::EmptyComponent { offset: 2 }));
+        assert_eq!(GemVersion::parse("1.0-"), Err(ParseError::EmptyComponent { offset: 4 }));
+        assert_eq!(GemVersion::parse(" 1.a_b"), Err(ParseError::UnexpectedCharacter { character: '_', offset: 4 }));
+    }
+}
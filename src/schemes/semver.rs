@@ -0,0 +1,298 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use super::super::errors::ParseError;
+use super::VersionScheme;
+
+/// A version following [Semantic Versioning 2.0.0](https://semver.org/).
+///
+/// Build metadata is kept but ignored for precedence, so `1.0.0+a == 1.0.0+b`.
+#[derive(Debug, Clone, Eq)]
+pub struct SemVer {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre_release: Vec<Identifier>,
+    pub build: Vec<String>,
+}
+
+/// One dot-separated pre-release identifier.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Identifier {
+    Numeric(u64),
+    AlphaNumeric(String),
+}
+
+/// Strict Semantic Versioning 2.0.0.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SemverScheme;
+
+impl VersionScheme for SemverScheme {
+    type Version = SemVer;
+
+    fn parse(&self, raw_version: &str) -> Result<SemVer, ParseError> {
+        SemVer::parse(raw_version)
+    }
+}
+
+impl SemVer {
+    pub fn parse(raw_version: &str) -> Result<SemVer, ParseError> {
+        let (raw_core, raw_build, build_offset) = match raw_version.find('+') {
+            Some(index) => (&raw_version[..index], Some(&raw_version[index + 1..]), index + 1),
+            None => (raw_version, None, 0),
+        };
+        let (raw_main, raw_pre_release, pre_release_offset) = match raw_core.find('-') {
+            Some(index) => (&raw_core[..index], Some(&raw_core[index + 1..]), index + 1),
+            None => (raw_core, None, 0),
+        };
+
+        let numbers: Vec<u64> = parse_main(raw_main)?;
+        let pre_release: Vec<Identifier> = match raw_pre_release {
+            Some(s) => split_identifiers(s, pre_release_offset)?
+                .into_iter()
+                .map(|(identifier, offset)| parse_pre_release_identifier(identifier, offset))
+                .collect::<Result<Vec<Identifier>, ParseError>>()?,
+            None => Vec::new(),
+        };
+        let build: Vec<String> = match raw_build {
+            Some(s) => split_identifiers(s, build_offset)?
+                .into_iter()
+                .map(|(identifier, _)| identifier.to_string())
+                .collect(),
+            None => Vec::new(),
+        };
+        Ok(SemVer {
+            major: numbers[0],
+            minor: numbers[1],
+            patch: numbers[2],
+            pre_release,
+            build,
+        })
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre_release.is_empty()
+    }
+
+    fn cmp_pre_release(&self, other: &SemVer) -> Ordering {
+        match [self.pre_release.is_empty(), other.pre_release.is_empty()] {
+            [true, true] => Ordering::Equal,
+            [true, false] => Ordering::Greater,
+            [false, true] => Ordering::Less,
+            [false, false] => self.pre_release.cmp(&other.pre_release),
+        }
+    }
+}
+
+fn parse_main(raw_main: &str) -> Result<Vec<u64>, ParseError> {
+    let mut numbers: Vec<u64> = Vec::new();
+    let mut offset: usize = 0;
+    for raw_number in raw_main.split('.') {
+        if numbers.len() == 3 {
+            return Err(ParseError::UnexpectedCharacter { character: '.', offset: offset - 1 })
+        }
+        numbers.push(parse_numeric(raw_number, offset)?);
+        offset += raw_number.len() + 1;
+    }
+    if numbers.len() < 3 {
+        return Err(ParseError::EmptyComponent { offset: raw_main.len() })
+    }
+    Ok(numbers)
+}
+
+fn parse_numeric(s: &str, offset: usize) -> Result<u64, ParseError> {
+    if s.is_empty() {
+        return Err(ParseError::EmptyComponent { offset })
+    }
+    if let Some((index, character)) = s.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        return Err(ParseError::UnexpectedCharacter { character, offset: offset + index })
+    }
+    if s.len() > 1 && s.starts_with('0') {
+        return Err(ParseError::LeadingZero { offset })
+    }
+    s.parse().map_err(|_| ParseError::NumericOverflow { offset })
+}
+
+pub(crate) fn split_identifiers(s: &str, offset: usize) -> Result<Vec<(&str, usize)>, ParseError> {
+    let mut identifiers: Vec<(&str, usize)> = Vec::new();
+    let mut identifier_offset: usize = offset;
+    for identifier in s.split('.') {
+        if identifier.is_empty() {
+            return Err(ParseError::EmptyComponent { offset: identifier_offset })
+        }
+        if let Some((index, character)) = identifier.char_indices().find(|(_, c)| !(c.is_ascii_alphanumeric() || *c == '-')) {
+            return Err(ParseError::UnexpectedCharacter { character, offset: identifier_offset + index })
+        }
+        identifiers.push((identifier, identifier_offset));
+        identifier_offset += identifier.len() + 1;
+    }
+    Ok(identifiers)
+}
+
+pub(crate) fn parse_pre_release_identifier(identifier: &str, offset: usize) -> Result<Identifier, ParseError> {
+    if identifier.chars().all(|c| c.is_ascii_digit()) {
+        Ok(Identifier::Numeric(parse_numeric(identifier, offset)?))
+    } else {
+        Ok(Identifier::AlphaNumeric(identifier.to_string()))
+    }
+}
+
+impl PartialOrd for SemVer {
+    fn partial_cmp(&self, other: &SemVer) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SemVer {
+    fn cmp(&self, other: &SemVer) -> Ordering {
+        let order: Ordering = [self.major, self.minor, self.patch].cmp(&[other.major, other.minor, other.patch]);
+        if order != Ordering::Equal {
+            return order
+        }
+
+        self.cmp_pre_release(other)
+    }
+}
+
+impl PartialEq for SemVer {
+    fn eq(&self, other: &SemVer) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Hash for SemVer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.major.hash(state);
+        self.minor.hash(state);
+        self.patch.hash(state);
+        self.pre_release.hash(state);
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Identifier) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Identifier {
+    fn cmp(&self, other: &Identifier) -> Ordering {
+        match (self, other) {
+            (Identifier::Numeric(x), Identifier::Numeric(y)) => x.cmp(y),
+            (Identifier::Numeric(_), Identifier::AlphaNumeric(_)) => Ordering::Less,
+            (Identifier::AlphaNumeric(_), Identifier::Numeric(_)) => Ordering::Greater,
+            (Identifier::AlphaNumeric(x), Identifier::AlphaNumeric(y)) => x.cmp(y),
+        }
+    }
+}
+
+impl FromStr for SemVer {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<SemVer, ParseError> {
+        SemVer::parse(s)
+    }
+}
+
+impl fmt::Display for SemVer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre_release.is_empty() {
+            let identifiers: Vec<String> = self.pre_release.iter().map(|i| i.to_string()).collect();
+            write!(f, "-{}", identifiers.join("."))?;
+        }
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Identifier::Numeric(n) => write!(f, "{}", n),
+            Identifier::AlphaNumeric(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn semver(s: &str) -> SemVer {
+        SemVer::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_spec_precedence_example() {
+        let ordered = [
+            "1.0.0-alpha", "1.0.0-alpha.1", "1.0.0-alpha.beta", "1.0.0-beta",
+            "1.0.0-beta.2", "1.0.0-beta.11", "1.0.0-rc.1", "1.0.0",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(semver(pair[0]) < semver(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn test_main_numbers_compared_numerically() {
+        assert!(semver("1.9.0") < semver("1.10.0"));
+        assert!(semver("1.10.0") < semver("1.11.0"));
+        assert!(semver("2.0.0") < semver("2.1.0"));
+        assert!(semver("2.1.0") < semver("2.1.1"));
+    }
+
+    #[test]
+    fn test_any_number_of_pre_release_identifiers() {
+        let version: SemVer = semver("1.0.0-alpha.1.2");
+
+        assert_eq!(version.pre_release, vec![
+            Identifier::AlphaNumeric("alpha".to_string()),
+            Identifier::Numeric(1),
+            Identifier::Numeric(2),
+        ]);
+        assert!(semver("1.0.0-alpha.1") < version);
+    }
+
+    #[test]
+    fn test_alphanumerics_compared_in_ascii_order() {
+        assert!(semver("1.0.0-Beta") < semver("1.0.0-alpha"));
+        assert!(semver("1.0.0-a-b") < semver("1.0.0-a-c"));
+    }
+
+    #[test]
+    fn test_build_metadata_ignored_for_precedence() {
+        assert_eq!(semver("1.0.0+20130313144700"), semver("1.0.0"));
+        assert_eq!(semver("1.0.0-beta+exp.sha.5114f85").cmp(&semver("1.0.0-beta")), Ordering::Equal);
+        assert_eq!(semver("1.0.0+exp.sha.5114f85").build, vec!["exp", "sha", "5114f85"]);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for raw_version in ["1.0.0", "1.0.0-alpha.1.2", "1.0.0-x-y.7+001.build-1", "10.20.30+meta"].iter() {
+            assert_eq!(semver(raw_version).to_string(), *raw_version);
+        }
+    }
+
+    #[test]
+    fn test_invalid_versions() {
+        assert_eq!(SemVer::parse("1.2"), Err(ParseError::EmptyComponent { offset: 3 }));
+        assert_eq!(SemVer::parse("1.2.3.4"), Err(ParseError::UnexpectedCharacter { character: '.', offset: 5 }));
+        assert_eq!(SemVer::parse("01.2.3"), Err(ParseError::LeadingZero { offset: 0 }));
+        assert_eq!(SemVer::parse("1.2.3-01"), Err(ParseError::LeadingZero { offset: 6 }));
+        assert_eq!(SemVer::parse("1.2.3-alpha..1"), Err(ParseError::EmptyComponent { offset: 12 }));
+        assert_eq!(SemVer::parse("1.2.3+"), Err(ParseError::EmptyComponent { offset: 6 }));
+        assert_eq!(SemVer::parse("1.2.3-al_pha"), Err(ParseError::UnexpectedCharacter { character: '_', offset: 8 }));
+        assert_eq!(SemVer::parse("v1.2.3"), Err(ParseError::UnexpectedCharacter { character: 'v', offset: 0 }));
+    }
+
+    #[test]
+    fn test_leading_zero_allowed_in_build_and_alphanumerics() {
+        assert!(SemVer::parse("1.2.3-0a+001").is_ok());
+    }
+}